@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+
+use super::*;
+
+/// every reading of one word of surface text, most likely first
+#[derive(Debug, Clone)]
+pub struct WordAnalysis {
+    pub word: String,
+    pub analyses: Vec<Keyword>,
+    /// the marker it stands for if it's a particle, like `NEG`, the verb next to it is read as carrying it
+    pub particle: Option<String>,
+}
+
+/// maps surface words back to the keywords that render to them
+///
+/// building one renders every form the language has, so keep it around when analyzing a lot of text
+pub struct Analyzer {
+    words: HashMap<String, Vec<Keyword>>,
+    /// negation and question particles by their form, with the marker they stand for
+    particles: HashMap<String, (&'static str, MarkerPlacement)>,
+}
+
+/// how far a keyword is from the plain form of its word, used to rank ambiguous readings
+///
+/// particles and articles come first since they're the most common words in any sentence,
+/// then bare roots, then anything carrying extra inflection
+fn markedness(keyword: &Keyword) -> usize {
+    match keyword {
+        Keyword::CompletiveAspect | Keyword::ProgressiveAspect | Keyword::HabitualAspect | Keyword::PerfectAspect
+            | Keyword::DefiniteArticle(_) | Keyword::IndefiniteArticle(_)
+            | Keyword::DeicticSpatialNoun(_) | Keyword::DeicticTemporalNoun(_) | Keyword::Pronoun(_, _, _, _)
+            | Keyword::Interrogative(_) | Keyword::Numeral(_) => 0,
+        Keyword::Nominative(_, NounNumber::Singular, Case::Nominative) | Keyword::Verbal(_, VerbForm::TensePresent, None, Polarity::Positive, Mood::Statement) => 1,
+        Keyword::Adjective(_) | Keyword::Prepositional(_) => 1,
+        Keyword::Nominative(_, _, _) | Keyword::Verbal(_, _, _, _, _) => 2,
+        Keyword::VerbalAdjective(_) | Keyword::AdjectAdjective(_) => 2,
+        Keyword::Defined(_, None, _) => 0,
+        Keyword::Defined(_, Some(_), _) => 2,
+    }
+}
+
+impl Lang {
+    /// every keyword this language can render, one for each root, part of speech and inflection
+    pub fn every_keyword(&self) -> Vec<Keyword> {
+        let deixes = [Deixis::NonSpatial, Deixis::Proximal, Deixis::Immediate, Deixis::Distal];
+
+        let mut keywords = vec![
+            Keyword::CompletiveAspect,
+            Keyword::ProgressiveAspect,
+            Keyword::HabitualAspect,
+            Keyword::PerfectAspect,
+        ];
+        for deixis in &deixes {
+            keywords.push(Keyword::DefiniteArticle(deixis.clone()));
+            keywords.push(Keyword::IndefiniteArticle(deixis.clone()));
+            if *deixis != Deixis::NonSpatial {
+                keywords.push(Keyword::DeicticSpatialNoun(deixis.clone()));
+                keywords.push(Keyword::DeicticTemporalNoun(deixis.clone()));
+            }
+        }
+
+        for key in self.forms.pronouns.keys() {
+            if let Some((person, number, clusivity, formality)) = parse_person_key(key) {
+                keywords.push(Keyword::Pronoun(person, number, clusivity, formality));
+            }
+        }
+
+        // verbs come bare and agreeing with every subject the language has endings for
+        let mut subjects = vec![None];
+        subjects.extend(self.forms.verb_agreement.keys().filter_map(|k| parse_person_key(k)).map(|(p, n, _, _)| Some((p, n))));
+
+        // negation and questions only change the verb itself when they're affixes, particles are words of their own
+        let affix = |marker: &Option<Marker>| marker.as_ref().is_some_and(|m| matches!(m.placement, MarkerPlacement::Prefix | MarkerPlacement::Suffix));
+        let mut moods = vec![(Polarity::Positive, Mood::Statement)];
+        if affix(&self.forms.negation) {moods.push((Polarity::Negative, Mood::Statement))}
+        if affix(&self.forms.question) {moods.push((Polarity::Positive, Mood::Question))}
+        if affix(&self.forms.negation) && affix(&self.forms.question) {moods.push((Polarity::Negative, Mood::Question))}
+
+        for word in self.forms.question_words.keys() {
+            keywords.push(Keyword::Interrogative(word.clone()));
+        }
+
+        // every number up to the square of the base, bigger ones are rare enough to not be worth rendering up front
+        if let Some(numerals) = &self.forms.numerals {
+            let radix = numerals.base.radix();
+            keywords.extend((0..(radix * radix).min(numerals.max() + 1)).map(Keyword::Numeral));
+        }
+
+        let mut numbers = vec![NounNumber::Singular, NounNumber::Plural];
+        if self.forms.noun_dual_suffix.is_some() {numbers.push(NounNumber::Dual)}
+        if self.forms.noun_paucal_suffix.is_some() {numbers.push(NounNumber::Paucal)}
+
+        // only as what roots.yaml allows each root to be used as
+        for (root, entry) in &self.roots {
+            if entry.allows("adj") {
+                keywords.push(Keyword::Adjective(root.clone()));
+            }
+            if entry.allows("nom") {
+                for case in Case::ALL.into_iter().filter(|c| *c == Case::Nominative || self.forms.noun_cases.contains_key(c.as_str())) {
+                    for number in &numbers {
+                        keywords.push(Keyword::Nominative(root.clone(), number.clone(), case.clone()));
+                    }
+                }
+            }
+            if entry.allows("verb") {
+                for form in VerbForm::ALL {
+                    for subject in &subjects {
+                        for (polarity, mood) in &moods {
+                            keywords.push(Keyword::Verbal(root.clone(), form.clone(), subject.clone(), polarity.clone(), mood.clone()));
+                        }
+                    }
+                }
+            }
+            if entry.allows("vadj") {
+                keywords.push(Keyword::VerbalAdjective(root.clone()));
+            }
+            if entry.allows("prep") {
+                keywords.push(Keyword::Prepositional(root.clone()));
+            }
+            if entry.allows("aadj") {
+                keywords.push(Keyword::AdjectAdjective(root.clone()));
+            }
+        }
+
+        // two root compounds in their plainest forms, longer ones are left out since there's no end to them
+        if self.forms.compounding.is_some() {
+            for modifier in self.roots.keys() {
+                for head in self.roots.keys().filter(|h| *h != modifier) {
+                    let compound = format!("{}+{}", modifier, head);
+                    if self.check_root("nom", &compound).is_ok() {
+                        keywords.push(Keyword::Nominative(compound.clone(), NounNumber::Singular, Case::Nominative));
+                        keywords.push(Keyword::Nominative(compound, NounNumber::Plural, Case::Nominative));
+                    }
+                }
+            }
+        }
+
+        // derived words in their plainest forms
+        for name in self.forms.derivations.keys() {
+            for root in self.roots.keys() {
+                let derived = format!("{}>{}", root, name);
+                let allows = |keyword: &str| self.check_root(keyword, &derived).is_ok();
+                if allows("nom") {
+                    keywords.push(Keyword::Nominative(derived.clone(), NounNumber::Singular, Case::Nominative));
+                    keywords.push(Keyword::Nominative(derived.clone(), NounNumber::Plural, Case::Nominative));
+                }
+                if allows("verb") {
+                    keywords.push(Keyword::Verbal(derived.clone(), VerbForm::TensePresent, None, Polarity::Positive, Mood::Statement));
+                }
+                if allows("adj") {
+                    keywords.push(Keyword::Adjective(derived));
+                }
+            }
+        }
+
+        for (name, definition) in &self.forms.keywords {
+            // every combination of parameter values
+            let mut combinations: Vec<Vec<String>> = vec![vec![]];
+            for parameter in &definition.parameters {
+                combinations = combinations.iter()
+                    .flat_map(|c| parameter.values.keys().map(move |v| [c.clone(), vec![v.clone()]].concat()))
+                    .collect();
+            }
+            let roots: Vec<Option<String>> = match definition.mold {
+                Some(_) => self.roots.iter().filter(|(_, r)| r.allows(name)).map(|(k, _)| Some(k.clone())).collect(),
+                None => vec![None],
+            };
+            for root in roots {
+                for values in &combinations {
+                    keywords.push(Keyword::Defined(name.clone(), root.clone(), values.clone()));
+                }
+            }
+        }
+
+        keywords
+    }
+
+    pub fn analyzer(&self) -> Analyzer {
+        Analyzer::new(self)
+    }
+
+    /// reads surface text like "it yinaat ha kadaron" back into keywords, word by word
+    pub fn analyze(&self, text: &str) -> Vec<WordAnalysis> {
+        self.analyzer().analyze(text)
+    }
+}
+
+impl Analyzer {
+    pub fn new(lang: &Lang) -> Analyzer {
+        let mut words: HashMap<String, Vec<Keyword>> = HashMap::new();
+        for keyword in lang.every_keyword() {
+            // anything that can't render can't have produced a word either
+            if let Ok(word) = keyword.render(lang) {
+                words.entry(word).or_default().push(keyword);
+            }
+        }
+        for analyses in words.values_mut() {
+            analyses.sort_by_key(markedness);
+        }
+        let mut particles = HashMap::new();
+        for (marker, gloss) in [(&lang.forms.negation, "NEG"), (&lang.forms.question, "Q")] {
+            if let Some(marker) = marker && matches!(marker.placement, MarkerPlacement::Before | MarkerPlacement::After) {
+                particles.insert(marker.form.clone(), (gloss, marker.placement));
+            }
+        }
+        Analyzer { words, particles }
+    }
+
+    /// the marker a word stands for if it's a negation or question particle, like `NEG` or `Q`
+    pub fn particle(&self, word: &str) -> Option<&str> {
+        let word = word.trim_matches(|c: char| c.is_ascii_punctuation()).to_lowercase();
+        self.particles.get(&word).map(|(gloss, _)| *gloss)
+    }
+
+    /// every keyword that renders to `word`, most likely first, empty if nothing does
+    pub fn analyze_word(&self, word: &str) -> Vec<Keyword> {
+        let word = word.trim_matches(|c: char| c.is_ascii_punctuation()).to_lowercase();
+        self.words.get(&word).cloned().unwrap_or_default()
+    }
+
+    pub fn analyze(&self, text: &str) -> Vec<WordAnalysis> {
+        let mut words: Vec<WordAnalysis> = text.split_whitespace()
+            .map(|word| WordAnalysis {
+                word: word.to_string(),
+                analyses: self.analyze_word(word),
+                particle: self.particle(word).map(String::from),
+            })
+            .collect();
+
+        // a verb with a particle looks no different from one without, the particle next to it says what it carries
+        for i in 0..words.len() {
+            let word = words[i].word.trim_matches(|c: char| c.is_ascii_punctuation()).to_lowercase();
+            let Some(&(gloss, placement)) = self.particles.get(&word) else {continue};
+            let verb = match placement {
+                MarkerPlacement::Before => Some(i + 1),
+                _ => i.checked_sub(1),
+            };
+            let Some(verb) = verb.and_then(|v| words.get_mut(v)) else {continue};
+            for keyword in &mut verb.analyses {
+                if let Keyword::Verbal(_, _, _, polarity, mood) = keyword {
+                    match gloss {
+                        "NEG" => *polarity = Polarity::Negative,
+                        _ => *mood = Mood::Question,
+                    }
+                }
+            }
+        }
+        words
+    }
+
+    /// every surface word the language can produce, along with its readings
+    pub fn words(&self) -> impl Iterator<Item = (&String, &Vec<Keyword>)> {
+        self.words.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the likeliest reading of every word of `markup` as rendered
+    fn read_back(lang: &Lang, markup: &str) -> Vec<Option<Keyword>> {
+        lang.analyze(&lang.render(markup).unwrap()).into_iter().map(|w| w.analyses.into_iter().next()).collect()
+    }
+
+    #[test]
+    fn reads_rendered_sentences_back() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let words = lang.analyze(&lang.render("artIndef{nspac} nom{young 1} aspPerf verb{run past}").unwrap());
+        assert_eq!(words[0].analyses, vec![Keyword::IndefiniteArticle(Deixis::NonSpatial)]);
+        assert_eq!(words[1].analyses, vec![Keyword::Nominative("young".to_string(), NounNumber::Plural, Case::Nominative)]);
+        assert_eq!(words[2].analyses, vec![Keyword::PerfectAspect]);
+        assert_eq!(words[3].analyses, vec![Keyword::Verbal("run".to_string(), VerbForm::TensePast, Some((Person::Third, Number::Plural)), Polarity::Positive, Mood::Statement)]);
+        assert!(lang.analyze("zzz").iter().all(|w| w.analyses.is_empty() && w.particle.is_none()));
+    }
+
+    #[test]
+    fn ambiguous_words_come_plainest_first() {
+        let mut lang = Lang::load("assets/lang").unwrap();
+        // a bare verb before the same form agreeing with a subject
+        let analyses = lang.analyzer().analyze_word("kadara");
+        assert_eq!(analyses[0], Keyword::Verbal("run".to_string(), VerbForm::TensePresent, None, Polarity::Positive, Mood::Statement));
+        assert!(analyses.len() > 1);
+
+        // an article before a noun, whichever renders first
+        lang.forms.article.definite.distal = lang.render("nom{cat 0}").unwrap().trim().to_string();
+        let analyses = lang.analyzer().analyze_word("Kita,");
+        assert_eq!(analyses, vec![Keyword::DefiniteArticle(Deixis::Distal), Keyword::Nominative("cat".to_string(), NounNumber::Singular, Case::Nominative)]);
+
+        // a bare noun before an inflected one
+        lang.forms.noun_plurality_suffix = String::new();
+        let analyses = lang.analyzer().analyze_word("wida");
+        assert_eq!(analyses, vec![Keyword::Nominative("water".to_string(), NounNumber::Singular, Case::Nominative), Keyword::Nominative("water".to_string(), NounNumber::Plural, Case::Nominative)]);
+    }
+
+    #[test]
+    fn particles_mark_the_verb_next_to_them() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let negated = lang.render("artDef{nspac} nom{cat 0} verb{sleep fut neg}").unwrap();
+        let words = lang.analyze(&negated);
+        let particle = words.iter().position(|w| w.particle.as_deref() == Some("NEG")).unwrap();
+        let Some(Keyword::Verbal(root, VerbForm::TenseFuture, _, Polarity::Negative, Mood::Statement)) = words[particle + 1].analyses.first() else {
+            panic!("{:?}", words);
+        };
+        assert_eq!(root, "sleep");
+
+        // the question particle goes after its verb
+        let read = read_back(&lang, "pron{3 sg} verb{sleep pres q}");
+        assert!(matches!(read[1], Some(Keyword::Verbal(_, _, _, Polarity::Positive, Mood::Question))), "{:?}", read);
+        assert_eq!(read[2], None);
+        assert_eq!(lang.analyzer().particle("ka?"), Some("Q"));
+    }
+
+    #[test]
+    fn reads_compounds_and_derived_words() {
+        let lang = Lang::load("assets/lang").unwrap();
+        assert_eq!(read_back(&lang, "nom{fire+water 0}"), vec![Some(Keyword::Nominative("fire+water".to_string(), NounNumber::Singular, Case::Nominative))]);
+        assert_eq!(read_back(&lang, "nom{run>agent 1}"), vec![Some(Keyword::Nominative("run>agent".to_string(), NounNumber::Plural, Case::Nominative))]);
+    }
+}
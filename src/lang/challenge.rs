@@ -0,0 +1,491 @@
+use std::fmt;
+
+use super::*;
+use super::english::*;
+
+/// what a translation is scored on, each on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Feature {
+    /// which roots it uses, by their key in roots.yaml
+    Roots,
+    /// the tense or form of every verb, like `PST`
+    Tense,
+    /// the number of every noun, like `PL`
+    Plurality,
+    /// articles and deictic nouns, like `DEF.DIST` or `LOC.PROX`
+    Deixis,
+    /// aspect particles, like `PROG`
+    Aspect,
+    /// every negated verb, as `NEG`
+    Polarity,
+}
+
+/// how an answer did on one feature, going by the same labels glosses use
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureScore {
+    pub feature: Feature,
+    /// in both the answer and the sentence
+    pub matched: Vec<String>,
+    /// in the sentence but not the answer
+    pub missing: Vec<String>,
+    /// in the answer but not the sentence
+    pub extra: Vec<String>,
+}
+
+/// how an answer to a challenge did, feature by feature
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChallengeScore {
+    /// only the features either the sentence or the answer has anything for
+    pub features: Vec<FeatureScore>,
+    /// words of an answer in the language that aren't words of it
+    pub unknown: Vec<String>,
+    /// misspelled words of an answer in the language, each with the word it was read as
+    pub corrected: Vec<(String, String)>,
+}
+
+/// a sentence for the player to translate, from the language into english or the other way around
+#[derive(Debug, Clone, PartialEq)]
+pub struct Challenge {
+    pub markup: String,
+    pub keywords: Vec<Keyword>,
+}
+
+type Fact = (Feature, String);
+
+/// articles as english writes them
+const DETERMINERS: [(&str, &str); 8] = [
+    ("the", "DEF.NSPAC"), ("this", "DEF.PROX"), ("these", "DEF.PROX"), ("that", "DEF.DIST"), ("those", "DEF.DIST"),
+    ("a", "INDF.NSPAC"), ("an", "INDF.NSPAC"), ("some", "INDF.NSPAC"),
+];
+
+/// words around a verb that carry its tense, aspect and polarity, and the tense they carry if any
+const AUXILIARIES: [(&str, Option<&str>); 24] = [
+    ("will", Some("FUT")), ("to", Some("INF")),
+    ("do", Some("PRS")), ("does", Some("PRS")), ("did", Some("PST")),
+    ("am", Some("PRS")), ("is", Some("PRS")), ("are", Some("PRS")), ("was", Some("PST")), ("were", Some("PST")),
+    ("be", None), ("been", None), ("being", None),
+    ("have", Some("PRS")), ("has", Some("PRS")), ("had", Some("PST")),
+    ("finish", Some("PRS")), ("finishes", Some("PRS")), ("finished", Some("PST")),
+    ("not", None), ("never", None), ("usually", None), ("always", None), ("often", None),
+];
+
+const PRONOUNS: [&str; 12] = ["i", "me", "you", "we", "us", "they", "them", "he", "him", "she", "her", "it"];
+
+impl Feature {
+    pub const ALL: [Feature; 6] = [Feature::Roots, Feature::Tense, Feature::Plurality, Feature::Deixis, Feature::Aspect, Feature::Polarity];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Feature::Roots => "roots",
+            Feature::Tense => "tense",
+            Feature::Plurality => "plurality",
+            Feature::Deixis => "deixis",
+            Feature::Aspect => "aspect",
+            Feature::Polarity => "polarity",
+        }
+    }
+}
+
+impl FeatureScore {
+    /// from 0 to 1, everything missing or extra counting against it
+    pub fn score(&self) -> f32 {
+        let total = self.matched.len() + self.missing.len() + self.extra.len();
+        if total == 0 {1.0} else {self.matched.len() as f32 / total as f32}
+    }
+
+    pub fn is_correct(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty()
+    }
+}
+
+impl ChallengeScore {
+    /// from 0 to 1, every feature counting the same
+    pub fn score(&self) -> f32 {
+        if self.features.is_empty() {
+            return 1.0;
+        }
+        self.features.iter().map(|f| f.score()).sum::<f32>() / self.features.len() as f32
+    }
+
+    pub fn is_correct(&self) -> bool {
+        self.unknown.is_empty() && self.features.iter().all(|f| f.is_correct())
+    }
+}
+
+impl fmt::Display for ChallengeScore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, feature) in self.features.iter().enumerate() {
+            if i > 0 {writeln!(f)?}
+            let total = feature.matched.len() + feature.missing.len();
+            write!(f, "{}: {}/{}", feature.feature.as_str(), feature.matched.len(), total)?;
+            if !feature.missing.is_empty() {write!(f, ", missing {}", feature.missing.join(" "))?}
+            if !feature.extra.is_empty() {write!(f, ", not wanted {}", feature.extra.join(" "))?}
+        }
+        if !self.unknown.is_empty() {
+            write!(f, "\nunknown words: {}", self.unknown.join(" "))?;
+        }
+        for (typed, word) in &self.corrected {
+            write!(f, "\n{} read as {}", typed, word)?;
+        }
+        Ok(())
+    }
+}
+
+/// everything a sentence is scored on
+fn facts(keywords: &[Keyword]) -> Vec<Fact> {
+    let mut facts = vec![];
+    for keyword in keywords {
+        facts.extend(keyword.roots().into_iter().map(|r| (Feature::Roots, r.to_string())));
+        let fact = match keyword {
+            Keyword::Verbal(_, form, _, polarity, _) => {
+                if *polarity == Polarity::Negative {facts.push((Feature::Polarity, "NEG".to_string()))}
+                (Feature::Tense, form.gloss_label().to_string())
+            },
+            Keyword::Nominative(_, number, _) => (Feature::Plurality, number.gloss_label().to_string()),
+            Keyword::DefiniteArticle(deixis) => (Feature::Deixis, format!("DEF.{}", deixis.gloss_label())),
+            Keyword::IndefiniteArticle(deixis) => (Feature::Deixis, format!("INDF.{}", deixis.gloss_label())),
+            Keyword::DeicticSpatialNoun(deixis) => (Feature::Deixis, format!("LOC.{}", deixis.gloss_label())),
+            Keyword::DeicticTemporalNoun(deixis) => (Feature::Deixis, format!("TIME.{}", deixis.gloss_label())),
+            Keyword::CompletiveAspect => (Feature::Aspect, "CMPL".to_string()),
+            Keyword::ProgressiveAspect => (Feature::Aspect, "PROG".to_string()),
+            Keyword::HabitualAspect => (Feature::Aspect, "HAB".to_string()),
+            Keyword::PerfectAspect => (Feature::Aspect, "PRF".to_string()),
+            _ => continue,
+        };
+        facts.push(fact);
+    }
+    facts
+}
+
+/// english can't tell a dual or paucal from a plural, or something right here from something near
+fn coarse(facts: Vec<Fact>) -> Vec<Fact> {
+    facts.into_iter()
+        .map(|(feature, value)| match value.as_str() {
+            "DU" | "PC" => (feature, "PL".to_string()),
+            _ => (feature, value.replace(".IMM", ".PROX")),
+        })
+        .collect()
+}
+
+/// pairs up what the sentence has with what the answer has, feature by feature
+fn compare(target: Vec<Fact>, answer: Vec<Fact>, unknown: Vec<String>, corrected: Vec<(String, String)>) -> ChallengeScore {
+    let mut features = vec![];
+    for feature in Feature::ALL {
+        let mut missing: Vec<String> = target.iter().filter(|(f, _)| *f == feature).map(|(_, v)| v.clone()).collect();
+        let (mut matched, mut extra) = (vec![], vec![]);
+        for (_, value) in answer.iter().filter(|(f, _)| *f == feature) {
+            match missing.iter().position(|m| m == value) {
+                Some(i) => matched.push(missing.remove(i)),
+                None => extra.push(value.clone()),
+            }
+        }
+        if !(matched.is_empty() && missing.is_empty() && extra.is_empty()) {
+            features.push(FeatureScore { feature, matched, missing, extra });
+        }
+    }
+    ChallengeScore { features, unknown, corrected }
+}
+
+/// what an english word turned out to be, for making sense of the words around it
+#[derive(Debug, Clone, PartialEq)]
+enum English<'a> {
+    Determiner(&'a str),
+    Auxiliary(&'a str),
+    Pronoun,
+    /// "here", "now" and the like, already labelled
+    Deictic(String),
+    Number,
+    /// a word of the language's, the root it means and how it's inflected
+    Root(&'a str, &'a Root, Inflection),
+    /// anything else, like an english preposition standing in for a case
+    Other,
+}
+
+impl Lang {
+    /// a challenge to translate `markup`
+    pub fn challenge(&self, markup: &str) -> Result<Challenge, Vec<MarkupError>> {
+        Ok(Challenge { markup: markup.to_string(), keywords: self.parse(markup)? })
+    }
+
+    /// reads the roots and grammar an english sentence has, as far as it can,
+    /// roots in `prefer` are picked over others with the same meaning
+    fn english_facts(&self, text: &str, prefer: &[&str]) -> Vec<Fact> {
+        // "didn't" is "did not", "won't" is "will not"
+        let mut words: Vec<String> = vec![];
+        for word in text.split(|c: char| c.is_whitespace() || c == '-') {
+            let word = word.trim_matches(|c: char| c.is_ascii_punctuation()).to_lowercase();
+            match word.strip_suffix("n't") {
+                Some("wo") => words.extend(["will".to_string(), "not".to_string()]),
+                Some(stem) => words.extend([stem.to_string(), "not".to_string()]),
+                None if !word.is_empty() => words.push(word),
+                None => {},
+            }
+        }
+
+        let tokens: Vec<English> = words.iter().enumerate().map(|(i, word)| {
+            let word = word.as_str();
+            if let Some((_, label)) = DETERMINERS.iter().find(|(d, _)| *d == word) {
+                return English::Determiner(label);
+            }
+            if AUXILIARIES.iter().any(|(a, _)| *a == word) {
+                return English::Auxiliary(word);
+            }
+            if PRONOUNS.contains(&word) {
+                return English::Pronoun;
+            }
+            let right = i > 0 && words[i - 1] == "right";
+            let deictic = match word {
+                "here" => Some(if right {"LOC.IMM"} else {"LOC.PROX"}),
+                "there" => Some("LOC.DIST"),
+                "now" => Some(if right {"TIME.IMM"} else {"TIME.PROX"}),
+                "then" => Some("TIME.DIST"),
+                _ => None,
+            };
+            if let Some(label) = deictic {
+                return English::Deictic(label.to_string());
+            }
+            if (0..100).any(|n| number_words(n) == word) || word == "few" {
+                return English::Number;
+            }
+            for (lemma, inflection) in uninflect(word) {
+                let mut found = self.lookup_english(&lemma);
+                found.sort_by_key(|(key, _)| !prefer.contains(key));
+                if let Some((key, root)) = found.first() {
+                    return English::Root(key, root, inflection);
+                }
+            }
+            English::Other
+        }).collect();
+
+        let mut facts = vec![];
+        // the auxiliaries since the last verb, and whether there's been a subject for the next one
+        let mut chain: Vec<&str> = vec![];
+        let mut subject = false;
+        for (i, token) in tokens.iter().enumerate() {
+            let previous = if i > 0 {tokens.get(i - 1)} else {None};
+            let next = tokens.get(i + 1);
+            // a noun phrase starts right after, a root that can also be a verb is taken as one
+            let phrase_next = matches!(next, Some(English::Determiner(_)) | Some(English::Pronoun) | Some(English::Number) | Some(English::Deictic(_)));
+            let noun_next = matches!(next, Some(English::Root(_, root, _)) if root.allows("nom") && !root.allows("verb"));
+            match token {
+                English::Determiner(label) => facts.push((Feature::Deixis, label.to_string())),
+                English::Auxiliary(word) => chain.push(word),
+                English::Pronoun => subject = true,
+                English::Deictic(label) => facts.push((Feature::Deixis, label.clone())),
+                English::Number => {},
+                English::Other => {
+                    // "a nearby cat" and "a distant cat" for indefinite articles with a deixis
+                    let deixis = match words[i].as_str() {
+                        "nearby" => "INDF.PROX",
+                        "distant" => "INDF.DIST",
+                        _ => {subject = true; continue},
+                    };
+                    if let Some(last) = facts.iter_mut().rev().find(|(f, v)| *f == Feature::Deixis && v == "INDF.NSPAC") {
+                        last.1 = deixis.to_string();
+                    }
+                },
+                English::Root(key, root, inflection) => {
+                    facts.push((Feature::Roots, key.to_string()));
+                    let after_determiner = matches!(previous, Some(English::Determiner(_)) | Some(English::Number) | Some(English::Other));
+                    let after_subject = matches!(previous, Some(English::Pronoun) | Some(English::Root(_, _, _)));
+                    if root.allows("prep") && *inflection == Inflection::Plain && (phrase_next || noun_next && !root.allows("nom")) {
+                        continue;
+                    }
+                    let verb = root.allows("verb") && !after_determiner && (
+                        !chain.is_empty()
+                            || *inflection == Inflection::Past
+                            || after_subject && matches!(inflection, Inflection::S | Inflection::Plain)
+                            || i == 0 && *inflection == Inflection::Plain
+                    );
+                    if verb {
+                        facts.extend(verb_facts(&chain, *inflection, subject));
+                        chain.clear();
+                        subject = false;
+                    } else if root.allows("nom") && !noun_next {
+                        let number = if *inflection == Inflection::S {"PL"} else {"SG"};
+                        facts.push((Feature::Plurality, number.to_string()));
+                        subject = true;
+                    }
+                },
+            }
+        }
+
+        // "they were" has no verb after its auxiliary, so the auxiliary is the verb
+        if subject && let Some(at) = chain.iter().rposition(|w| !["not", "never", "usually", "always", "often"].contains(w)) {
+            let verb = chain.remove(at);
+            let base = match verb {
+                "am" | "is" | "are" | "was" | "were" | "been" | "being" => "be",
+                "has" | "had" => "have",
+                "does" | "did" => "do",
+                other => other,
+            };
+            if let Some((key, _)) = self.lookup_english(base).into_iter().min_by_key(|(key, _)| !prefer.contains(key)) {
+                facts.push((Feature::Roots, key.to_string()));
+                // its own tense, if it has one, wins over whatever the rest of the chain would give a plain verb
+                let tense = AUXILIARIES.iter().find(|(a, _)| *a == verb).and_then(|(_, t)| *t);
+                facts.extend(verb_facts(&chain, Inflection::Plain, subject).into_iter().map(|(feature, value)| match (feature, tense) {
+                    (Feature::Tense, Some(tense)) => (feature, tense.to_string()),
+                    _ => (feature, value),
+                }));
+            }
+        }
+        facts
+    }
+}
+
+/// the tense, aspects and polarity of a verb from the auxiliaries before it and its own inflection,
+/// `subject` being whether it has one, a bare verb without one is an order
+fn verb_facts(chain: &[&str], inflection: Inflection, subject: bool) -> Vec<Fact> {
+    let has = |words: &[&str]| chain.iter().any(|w| words.contains(w));
+    let mut facts = vec![];
+    let tensed = chain.iter().find_map(|w| AUXILIARIES.iter().find(|(a, _)| a == w).and_then(|(_, t)| *t));
+    let tense = match tensed {
+        _ if has(&["will"]) => "FUT",
+        _ if chain.last() == Some(&"to") || chain.ends_with(&["to", "not"]) => "INF",
+        _ if !subject && (chain.is_empty() || chain[0] == "do") && inflection == Inflection::Plain => "IMP",
+        Some(tense) if tense != "INF" => tense,
+        _ if inflection == Inflection::Past && !has(&["have", "has", "had"]) => "PST",
+        _ => "PRS",
+    };
+    facts.push((Feature::Tense, tense.to_string()));
+    if has(&["am", "is", "are", "was", "were", "be", "been", "being"]) && inflection == Inflection::Ing {
+        facts.push((Feature::Aspect, "PROG".to_string()));
+    }
+    // "had run" looks no different from "had" and a bare "run", so anything after a "have" but "to" will do
+    if has(&["have", "has", "had"]) && !has(&["to"]) && inflection != Inflection::Ing || has(&["been"]) {
+        facts.push((Feature::Aspect, "PRF".to_string()));
+    }
+    if has(&["finish", "finishes", "finished"]) && inflection == Inflection::Ing {
+        facts.push((Feature::Aspect, "CMPL".to_string()));
+    }
+    if has(&["usually", "always", "often"]) {
+        facts.push((Feature::Aspect, "HAB".to_string()));
+    }
+    if has(&["not", "never"]) {
+        facts.push((Feature::Polarity, "NEG".to_string()));
+    }
+    facts
+}
+
+impl Challenge {
+    /// the sentence in the language, for asking for it in english
+    pub fn sentence(&self, lang: &Lang) -> Result<String, String> {
+        let words = lang.sentence_words(&self.keywords)?;
+        Ok(words.into_iter().map(|w| w.surface).collect::<Vec<String>>().join(" "))
+    }
+
+    /// the sentence in english, for asking for it in the language
+    pub fn english(&self, lang: &Lang) -> String {
+        lang.english_sentence(&self.keywords)
+    }
+
+    /// scores an english translation of the sentence, as well as english can be read without a parser,
+    /// duals and paucals count as plurals and immediate deixis as proximal since english doesn't tell them apart
+    pub fn score_english(&self, lang: &Lang, answer: &str) -> ChallengeScore {
+        let target = facts(&self.keywords);
+        let roots: Vec<&str> = self.keywords.iter().flat_map(|k| k.roots()).collect();
+        compare(coarse(target), coarse(lang.english_facts(answer, &roots)), vec![], vec![])
+    }
+
+    /// scores the sentence as written in the language by the player, word by word,
+    /// taking whichever reading of an ambiguous or misspelled word fits the sentence best
+    ///
+    /// `analyzer` is `lang.analyzer()`, worth keeping around between answers
+    pub fn score_conlang(&self, analyzer: &Analyzer, answer: &str) -> ChallengeScore {
+        let target = facts(&self.keywords);
+        let (mut answer_facts, mut unknown, mut corrected) = (vec![], vec![], vec![]);
+        for word in answer.split_whitespace() {
+            let bare = word.trim_matches(|c: char| c.is_ascii_punctuation()).to_lowercase();
+            match analyzer.particle(&bare) {
+                Some("NEG") => {
+                    answer_facts.push((Feature::Polarity, "NEG".to_string()));
+                    continue;
+                },
+                Some(_) => continue,
+                None => (),
+            }
+            let fits = |keyword: &Keyword| facts(std::slice::from_ref(keyword)).iter().filter(|f| target.contains(f)).count();
+            // a misspelled word is read as whichever of the closest words fits best
+            let matches = analyzer.closest(&bare, 5);
+            let closest = matches.iter().take_while(|m| m.distance == matches[0].distance);
+            let readings = closest.flat_map(|m| m.analyses.iter().map(move |k| (&m.word, k)));
+            // `max_by_key` keeps the last of equals, and the analyzer puts the likeliest reading first
+            match readings.collect::<Vec<_>>().into_iter().rev().max_by_key(|(_, k)| fits(k)) {
+                Some((surface, keyword)) => {
+                    if *surface != bare {corrected.push((word.to_string(), surface.clone()))}
+                    answer_facts.extend(facts(std::slice::from_ref(keyword)));
+                },
+                None => unknown.push(word.to_string()),
+            }
+        }
+        compare(target, answer_facts, unknown, corrected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATS_SLEPT: &str = "artDef{nspac} nom{cat 1} verb{sleep past}";
+    const CAT_WONT_SLEEP: &str = "artDef{nspac} nom{cat 0} verb{sleep fut neg}";
+    const CAT: &str = "artDef{nspac} nom{cat 0} aspProg verb{sleep pres} artDef{nspac} nom{fire 0 loc}";
+
+    /// the features an answer got wrong, by name
+    fn wrong(score: &ChallengeScore) -> Vec<&str> {
+        score.features.iter().filter(|f| !f.is_correct()).map(|f| f.feature.as_str()).collect()
+    }
+
+    #[test]
+    fn scores_english_feature_by_feature() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let challenge = lang.challenge(CATS_SLEPT).unwrap();
+        assert_eq!(challenge.english(&lang), "The cats slept.");
+        assert!(challenge.score_english(&lang, "The cats slept.").is_correct());
+        assert_eq!(wrong(&challenge.score_english(&lang, "the cats sleep")), vec!["tense"]);
+        assert_eq!(wrong(&challenge.score_english(&lang, "the cat slept")), vec!["plurality"]);
+
+        let challenge = lang.challenge(CAT).unwrap();
+        assert!(challenge.score_english(&lang, "the cat is sleeping at the fire").is_correct());
+        assert_eq!(wrong(&challenge.score_english(&lang, "the cat sleeps at the fire")), vec!["aspect"]);
+    }
+
+    #[test]
+    fn contractions_are_read_as_auxiliaries() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let challenge = lang.challenge(CAT_WONT_SLEEP).unwrap();
+        assert!(challenge.score_english(&lang, "the cat won't sleep").is_correct());
+        assert_eq!(wrong(&challenge.score_english(&lang, "the cat didn't sleep")), vec!["tense"]);
+
+        let challenge = lang.challenge(CATS_SLEPT).unwrap();
+        assert_eq!(wrong(&challenge.score_english(&lang, "the cats didn't sleep")), vec!["polarity"]);
+    }
+
+    #[test]
+    fn a_lone_auxiliary_is_the_verb() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let challenge = lang.challenge("pron{3 pl} verb{be past}").unwrap();
+        assert!(challenge.score_english(&lang, "they were").is_correct());
+        assert_eq!(wrong(&challenge.score_english(&lang, "they are")), vec!["tense"]);
+        assert_eq!(wrong(&challenge.score_english(&lang, "they weren't")), vec!["polarity"]);
+    }
+
+    #[test]
+    fn scores_the_language_word_by_word() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let analyzer = lang.analyzer();
+        let challenge = lang.challenge(CATS_SLEPT).unwrap();
+        assert_eq!(challenge.sentence(&lang).unwrap(), "nan kitu saparoni");
+        assert!(challenge.score_conlang(&analyzer, "Nan kitu saparoni.").is_correct());
+        assert_eq!(wrong(&challenge.score_conlang(&analyzer, "nan kita saparoni")), vec!["plurality"]);
+
+        let score = challenge.score_conlang(&analyzer, "nan kitu saparoni zzzzzzzz");
+        assert_eq!(score.unknown, vec!["zzzzzzzz"]);
+        let score = challenge.score_conlang(&analyzer, "nan kitu saparuni");
+        assert_eq!(score.corrected, vec![("saparuni".to_string(), "saparoni".to_string())]);
+        assert!(score.is_correct());
+
+        let challenge = lang.challenge(CAT_WONT_SLEEP).unwrap();
+        assert!(challenge.score_conlang(&analyzer, "nan kita ma saparil").is_correct());
+        assert_eq!(wrong(&challenge.score_conlang(&analyzer, "nan kita saparil")), vec!["polarity"]);
+    }
+}
@@ -0,0 +1,178 @@
+use super::*;
+
+/// a root as written in markup, compounded out of several with `+` and derived with `>`, like `fire+water>dim`
+///
+/// roots are written modifier first and head last whatever order the language puts them in
+#[derive(Debug, Clone, PartialEq)]
+pub struct RootSpec<'a> {
+    pub roots: Vec<&'a str>,
+    /// applied in order to the whole compound
+    pub derivations: Vec<&'a str>,
+}
+
+impl<'a> RootSpec<'a> {
+    pub fn parse(text: &'a str) -> RootSpec<'a> {
+        let mut pieces = text.split('>');
+        let roots = pieces.next().unwrap_or_default().split('+').collect();
+        RootSpec { roots, derivations: pieces.collect() }
+    }
+}
+
+impl Lang {
+    /// checks a root written for `keyword` against roots.yaml and the derivations and compounding this language has,
+    /// errors come with a hint
+    pub fn check_root(&self, keyword: &str, text: &str) -> Result<(), (String, String)> {
+        let spec = RootSpec::parse(text);
+        if spec.roots.iter().chain(&spec.derivations).any(|p| p.is_empty()) {
+            return Err((format!("`{}` is missing a root or derivation", text), "write it like `fire+water>dim`".to_string()));
+        }
+        if spec.roots.len() > 1 && self.forms.compounding.is_none() {
+            return Err(("this language has no compound words".to_string(), "add `compounding` to forms.yaml".to_string()));
+        }
+        for name in &spec.roots {
+            if !self.roots.contains_key(*name) {
+                return Err((format!("no root `{}`", name), "add it to roots.yaml".to_string()));
+            }
+        }
+
+        // the head has to be usable as whatever makes the word, the first derivation if it says what it takes,
+        // otherwise the keyword itself
+        let head = spec.roots[spec.roots.len() - 1];
+        let (usage, allowed) = match spec.derivations.first().and_then(|d| self.forms.derivations.get(*d)) {
+            Some(derivation) if !derivation.from.is_empty() => (
+                format!("take `{}`", spec.derivations[0]),
+                derivation.from.iter().any(|k| self.roots[head].allows(k)),
+            ),
+            _ => (format!("be used with `{}`", keyword), self.roots[head].allows(keyword)),
+        };
+        if !allowed {
+            return Err((
+                format!("`{}` can't {}", head, usage),
+                format!("roots.yaml only allows it with {}", self.roots[head].keywords.join(", ")),
+            ));
+        }
+
+        let mut molds = 0;
+        for name in &spec.derivations {
+            let Some(derivation) = self.forms.derivations.get(*name) else {
+                let names: Vec<&str> = self.forms.derivations.keys().map(|d| d.as_str()).collect();
+                return Err((
+                    format!("no derivation `{}`", name),
+                    if names.is_empty() {"add `derivations` to forms.yaml".to_string()} else {format!("use one of {}", names.join(", "))},
+                ));
+            };
+            if !derivation.keywords.is_empty() && !derivation.keywords.iter().any(|k| k == keyword) {
+                return Err((
+                    format!("`{}` can't be used with `{}`", name, keyword),
+                    format!("use it with {}", derivation.keywords.join(" or ")),
+                ));
+            }
+            if derivation.mold.is_some() {molds += 1}
+        }
+        if molds > 1 {
+            return Err((
+                "only one derivation with a mold of its own can go on a root".to_string(),
+                "keep one of them, or give the others a `suffix` instead".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// a root put in the `part` mold and glossed `root.LABEL`, or just `root` without a label,
+    /// or a compound or derived word built from roots
+    pub(super) fn stem(&self, part: &str, root: &str, label: &str) -> Result<Vec<Morpheme>, String> {
+        let spec = RootSpec::parse(root);
+        let derivations = spec.derivations.iter()
+            .map(|d| self.forms.derivations.get(*d).ok_or(format!("No derivation `{}` in forms.yaml", d)))
+            .collect::<Result<Vec<&Derivation>, String>>()?;
+        // a derivation with a mold of its own stands in for the keyword's
+        let (part, label) = derivations.iter()
+            .find_map(|d| d.mold.as_deref().map(|mold| (mold, d.gloss.as_str())))
+            .unwrap_or((part, label));
+
+        let word = |part: &str, root: &str, gloss: String| -> Result<Morpheme, String> {
+            Ok(match self.roots.get(root).and_then(|r| r.irregular(part, None)) {
+                Some(form) => Morpheme::irregular(form, gloss),
+                None => Morpheme::new(Keyword::format_base(part, root, self)?, gloss),
+            })
+        };
+        let Some((head, modifiers)) = spec.roots.split_last() else {return Err("No root given".to_string())};
+        let gloss = if label.is_empty() {head.to_string()} else {format!("{}.{}", head, label)};
+        let mut words = vec![word(part, head, gloss)?];
+        if let Some(compounding) = &self.forms.compounding {
+            let mold = compounding.mold.as_deref().unwrap_or("nominative");
+            let modifiers = modifiers.iter()
+                .map(|m| word(mold, m, m.to_string()))
+                .collect::<Result<Vec<Morpheme>, String>>()?;
+            words = match compounding.head {
+                Placement::Before => [words, modifiers].concat(),
+                Placement::After => [modifiers, words].concat(),
+            };
+        }
+
+        let link = self.forms.compounding.as_ref().map(|c| c.link.as_str()).unwrap_or_default();
+        let mut morphemes = vec![];
+        for (i, word) in words.into_iter().enumerate() {
+            if i > 0 && !link.is_empty() {
+                morphemes.push(Morpheme::new(link, "LNK"));
+            }
+            morphemes.push(word);
+        }
+        for derivation in derivations.iter().filter(|d| !d.suffix.is_empty()) {
+            push_suffix(&mut morphemes, &derivation.suffix, &derivation.gloss);
+        }
+        Ok(morphemes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gloss(lang: &Lang, markup: &str) -> (String, String) {
+        let word = lang.gloss(markup).unwrap().words.remove(0);
+        (word.segmented, word.gloss)
+    }
+
+    #[test]
+    fn parses_compounds_and_derivations() {
+        assert_eq!(RootSpec::parse("fire"), RootSpec { roots: vec!["fire"], derivations: vec![] });
+        assert_eq!(RootSpec::parse("fire+water>dim>abst"), RootSpec { roots: vec!["fire", "water"], derivations: vec!["dim", "abst"] });
+        assert_eq!(RootSpec::parse("fire+"), RootSpec { roots: vec!["fire", ""], derivations: vec![] });
+        assert_eq!(RootSpec::parse(">dim"), RootSpec { roots: vec![""], derivations: vec!["dim"] });
+    }
+
+    #[test]
+    fn renders_compounds_and_derivations() {
+        let mut lang = Lang::load("assets/lang").unwrap();
+        // a mold of its own glosses the stem by the derivation, a suffix goes on before any inflection
+        assert_eq!(gloss(&lang, "nom{run>agent 0}"), ("kadador".into(), "run.AGT".into()));
+        assert_eq!(gloss(&lang, "nom{run>agent>dim 1}"), ("kadador-ito-at".into(), "run.AGT-DIM-PL".into()));
+        assert_eq!(gloss(&lang, "verb{sleep>caus pres}"), ("sopoar-a".into(), "sleep.CAUS-PRS".into()));
+        // the head comes last here, and the endings go on the whole compound
+        assert_eq!(gloss(&lang, "nom{fire+water>dim 0 loc}"), ("fiya-wida-ito-ne".into(), "fire-water-DIM-LOC".into()));
+        assert_eq!(lang.render("nom{fire+water 0}").unwrap().trim(), "fiyawida");
+
+        lang.forms.compounding = Some(Compounding { head: Placement::Before, link: "o".to_string(), mold: None });
+        assert_eq!(gloss(&lang, "nom{fire+water 0}"), ("wida-o-fiya".into(), "water-LNK-fire".into()));
+    }
+
+    #[test]
+    fn check_root_says_what_is_wrong() {
+        let mut lang = Lang::load("assets/lang").unwrap();
+        let error = |keyword: &str, text: &str| lang.check_root(keyword, text).unwrap_err().0;
+        assert!(lang.check_root("nom", "fire+water>dim").is_ok());
+        assert!(lang.check_root("nom", "run>agent").is_ok());
+        assert_eq!(error("nom", "fire+"), "`fire+` is missing a root or derivation");
+        assert_eq!(error("nom", "fire+nope"), "no root `nope`");
+        assert_eq!(error("prep", "cat"), "`cat` can't be used with `prep`");
+        // the first derivation's `from` decides what the head has to be usable as
+        assert_eq!(error("nom", "cat>agent"), "`cat` can't take `agent`");
+        assert_eq!(error("nom", "run>nope"), "no derivation `nope`");
+        assert_eq!(error("verb", "run>dim"), "`dim` can't be used with `verb`");
+        assert_eq!(error("nom", "run>agent>abst"), "only one derivation with a mold of its own can go on a root");
+
+        lang.forms.compounding = None;
+        assert_eq!(lang.check_root("nom", "fire+water").unwrap_err().0, "this language has no compound words");
+    }
+}
@@ -0,0 +1,157 @@
+use super::*;
+
+/// lowercased, without the "to" of verbs or the article of nouns, so "To Run" finds "run"
+pub fn normalize(english: &str) -> String {
+    let english = english.trim().to_lowercase();
+    for prefix in ["to ", "a ", "an ", "the "] {
+        if let Some(rest) = english.strip_prefix(prefix) {
+            return rest.trim().to_string();
+        }
+    }
+    english
+}
+
+impl Root {
+    /// the english words it means, its gloss split at commas, or `key` when it has no gloss
+    pub fn english<'a>(&'a self, key: &'a str) -> Vec<&'a str> {
+        if self.gloss.is_empty() {
+            return vec![key];
+        }
+        self.gloss.split(',').map(|g| g.trim()).filter(|g| !g.is_empty()).collect()
+    }
+
+    /// the english for it used with `keyword`, like `verb`, from `senses` if it has one for it,
+    /// otherwise the first word of its gloss that fits, a "to" one for verbs and any other for the rest
+    pub fn english_as(&self, key: &str, keyword: &str) -> String {
+        if let Some(sense) = self.senses.get(keyword) {
+            return sense.clone();
+        }
+        let english = self.english(key);
+        let verb = |e: &&&str| e.starts_with("to ");
+        let fitting = match keyword {
+            "verb" => english.iter().find(verb),
+            _ => english.iter().find(|e| !verb(e)),
+        };
+        normalize(fitting.or(english.first()).unwrap_or(&key))
+    }
+}
+
+impl Lang {
+    /// the root under `key` in roots.yaml
+    pub fn root(&self, key: &str) -> Option<&Root> {
+        self.roots.get(key)
+    }
+
+    /// roots meaning `word`, going by their key, their gloss and their senses, ignoring case
+    pub fn lookup_english(&self, word: &str) -> Vec<(&str, &Root)> {
+        let word = normalize(word);
+        self.roots.iter()
+            .filter(|(key, root)| {
+                normalize(key) == word
+                    || root.english(key).iter().any(|e| normalize(e) == word)
+                    || root.senses.values().any(|s| normalize(s) == word)
+            })
+            .map(|(key, root)| (key.as_str(), root))
+            .collect()
+    }
+
+    /// roots with these consonants, like `kd`, a vowel melody on either side is ignored
+    pub fn lookup_root(&self, consonants: &str) -> Vec<(&str, &Root)> {
+        let bare = |c: &str| c.split('/').next().unwrap_or_default().to_string();
+        self.roots.iter()
+            .filter(|(_, root)| bare(&root.consonants) == bare(consonants))
+            .map(|(key, root)| (key.as_str(), root))
+            .collect()
+    }
+
+    /// roots in a semantic field like "animals", ignoring case
+    pub fn roots_in_field(&self, field: &str) -> Vec<(&str, &Root)> {
+        self.roots.iter()
+            .filter(|(_, root)| root.field.as_ref().is_some_and(|f| f.eq_ignore_ascii_case(field)))
+            .map(|(key, root)| (key.as_str(), root))
+            .collect()
+    }
+
+    /// every semantic field roots.yaml uses, in order
+    pub fn fields(&self) -> Vec<&str> {
+        let mut fields: Vec<&str> = self.roots.values().filter_map(|r| r.field.as_deref()).collect();
+        fields.sort();
+        fields.dedup();
+        fields
+    }
+
+    /// makes sure the keywords roots.yaml allows roots to be used with exist and its examples parse,
+    /// `text` is roots.yaml itself, for line numbers
+    pub(super) fn check_dictionary(&self, text: &str) -> Result<(), LangError> {
+        let error = |path: &[&str], message: String| LangError {
+            file: "roots.yaml".to_string(),
+            path: path.join("."),
+            line: line_of(text, path),
+            message,
+        };
+        let root_keywords = self.forms.root_keywords();
+        for (key, root) in &self.roots {
+            if let Some(keyword) = root.keywords.iter().find(|k| !root_keywords.contains(&k.as_str())) {
+                return Err(error(&[key, "keywords"], format!("`{}` doesn't take a root, use one of {}", keyword, root_keywords.join(", "))));
+            }
+            if let Some(keyword) = root.senses.keys().find(|k| !root_keywords.contains(&k.as_str())) {
+                return Err(error(&[key, "senses", keyword], format!("`{}` doesn't take a root, use one of {}", keyword, root_keywords.join(", "))));
+            }
+            for example in &root.examples {
+                if let Err(errors) = self.parse(example) {
+                    return Err(error(&[key, "examples"], format!("example `{}` doesn't parse: {}", example, errors[0])));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys<'a>(found: Vec<(&'a str, &Root)>) -> Vec<&'a str> {
+        found.into_iter().map(|(key, _)| key).collect()
+    }
+
+    #[test]
+    fn looks_roots_up_by_english_field_and_consonants() {
+        let lang = Lang::load("assets/lang").unwrap();
+        assert_eq!(keys(lang.lookup_english("To Sleep")), vec!["sleep"]);
+        assert_eq!(keys(lang.lookup_english("strike")), vec!["hit"]);
+        // senses count too
+        assert_eq!(keys(lang.lookup_english("toward")), vec!["face"]);
+        assert!(lang.lookup_english("spoon").is_empty());
+
+        assert_eq!(keys(lang.roots_in_field("Animals")), vec!["cat"]);
+        assert!(lang.roots_in_field("cooking").is_empty());
+        let fields = lang.fields();
+        assert!(fields.contains(&"animals") && fields.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(keys(lang.lookup_root("wd")), vec!["water"]);
+    }
+
+    #[test]
+    fn english_fits_the_keyword() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let root = |key: &str| lang.root(key).unwrap();
+        assert_eq!(root("sleep").english("sleep"), vec!["to sleep", "sleep"]);
+        assert_eq!(root("sleep").english_as("sleep", "verb"), "sleep");
+        assert_eq!(root("face").english_as("face", "prep"), "toward");
+        assert_eq!(root("face").english_as("face", "nom"), "face");
+        assert_eq!(normalize(" The Cat "), "cat");
+    }
+
+    #[test]
+    fn rejects_keywords_a_root_isnt_allowed_with() {
+        let mut lang = Lang::load("assets/lang").unwrap();
+        let errors = lang.parse("prep{cat} nom{cat 0}").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("`cat` can't be used with `prep`"), "{}", errors[0]);
+
+        lang.roots.get_mut("cat").unwrap().keywords.push("artDef".to_string());
+        let error = lang.check_dictionary("").unwrap_err();
+        assert_eq!(error.path, "cat.keywords");
+        assert!(error.message.starts_with("`artDef` doesn't take a root"));
+    }
+}
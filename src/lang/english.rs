@@ -0,0 +1,264 @@
+// just enough english inflection to translate markup into readable sentences,
+// everything works on the first word of a verb like "fall asleep" and the last word of a noun
+
+/// base, past and past participle of the irregular verbs a gloss is likely to use
+const IRREGULAR_VERBS: [(&str, &str, &str); 48] = [
+    ("be", "was", "been"), ("have", "had", "had"), ("do", "did", "done"), ("go", "went", "gone"),
+    ("run", "ran", "run"), ("sleep", "slept", "slept"), ("hit", "hit", "hit"), ("strike", "struck", "struck"),
+    ("eat", "ate", "eaten"), ("drink", "drank", "drunk"), ("see", "saw", "seen"), ("come", "came", "come"),
+    ("take", "took", "taken"), ("make", "made", "made"), ("know", "knew", "known"), ("give", "gave", "given"),
+    ("find", "found", "found"), ("think", "thought", "thought"), ("tell", "told", "told"), ("say", "said", "said"),
+    ("feel", "felt", "felt"), ("bring", "brought", "brought"), ("begin", "began", "begun"), ("keep", "kept", "kept"),
+    ("hold", "held", "held"), ("write", "wrote", "written"), ("stand", "stood", "stood"), ("hear", "heard", "heard"),
+    ("sit", "sat", "sat"), ("speak", "spoke", "spoken"), ("lie", "lay", "lain"), ("fall", "fell", "fallen"),
+    ("grow", "grew", "grown"), ("lose", "lost", "lost"), ("break", "broke", "broken"), ("cut", "cut", "cut"),
+    ("rise", "rose", "risen"), ("fly", "flew", "flown"), ("sing", "sang", "sung"), ("swim", "swam", "swum"),
+    ("fight", "fought", "fought"), ("throw", "threw", "thrown"), ("catch", "caught", "caught"), ("hide", "hid", "hidden"),
+    ("wake", "woke", "woken"), ("bite", "bit", "bitten"), ("blow", "blew", "blown"), ("burn", "burnt", "burnt"),
+];
+
+/// singular and plural of irregular nouns
+const IRREGULAR_NOUNS: [(&str, &str); 16] = [
+    ("child", "children"), ("person", "people"), ("man", "men"), ("woman", "women"),
+    ("mouse", "mice"), ("foot", "feet"), ("tooth", "teeth"), ("goose", "geese"),
+    ("fish", "fish"), ("sheep", "sheep"), ("deer", "deer"), ("ox", "oxen"),
+    ("leaf", "leaves"), ("wolf", "wolves"), ("knife", "knives"), ("life", "lives"),
+];
+
+fn is_vowel(c: char) -> bool {
+    "aeiou".contains(c)
+}
+
+fn ends_with_consonant_y(word: &str) -> bool {
+    let mut chars = word.chars().rev();
+    chars.next() == Some('y') && chars.next().is_some_and(|c| !is_vowel(c))
+}
+
+fn ends_with_sibilant(word: &str) -> bool {
+    ["s", "x", "z", "ch", "sh"].iter().any(|e| word.ends_with(e))
+}
+
+/// whether the last consonant doubles before an ending, like "run" to "running",
+/// only for one syllable words ending in a single vowel and consonant
+fn doubles(word: &str) -> bool {
+    let chars: Vec<char> = word.chars().collect();
+    let [.., a, b, c] = chars[..] else {return false};
+    chars.iter().filter(|c| is_vowel(**c)).count() == 1 && !is_vowel(a) && is_vowel(b) && !is_vowel(c) && !"wxy".contains(c)
+}
+
+/// `ending` on a word, dropping a silent e, doubling its last consonant or turning a final y to i when it has to
+fn suffixed(word: &str, ending: &str) -> String {
+    let silent_e = word.ends_with('e') && (ending.starts_with('e') || !word.ends_with("ee") && ending.starts_with(is_vowel));
+    if silent_e {
+        format!("{}{}", &word[..word.len() - 1], ending)
+    } else if ends_with_consonant_y(word) && !ending.starts_with('i') {
+        format!("{}i{}", &word[..word.len() - 1], if ending == "s" {"es"} else {ending})
+    } else if doubles(word) && ending.starts_with(is_vowel) && let Some(last) = word.chars().last() {
+        format!("{}{}{}", word, last, ending)
+    } else {
+        format!("{}{}", word, ending)
+    }
+}
+
+/// applies `f` to the first word of a phrase
+fn first_word(phrase: &str, f: impl Fn(&str) -> String) -> String {
+    match phrase.split_once(' ') {
+        Some((first, rest)) => format!("{} {}", f(first), rest),
+        None => f(phrase),
+    }
+}
+
+fn irregular_verb(verb: &str) -> Option<&'static (&'static str, &'static str, &'static str)> {
+    IRREGULAR_VERBS.iter().find(|(base, _, _)| *base == verb)
+}
+
+pub(super) fn plural_noun(noun: &str) -> String {
+    let (rest, last) = noun.rsplit_once(' ').map_or(("", noun), |(r, l)| (r, l));
+    let last = match IRREGULAR_NOUNS.iter().find(|(singular, _)| *singular == last) {
+        Some((_, plural)) => plural.to_string(),
+        None if ends_with_sibilant(last) => format!("{}es", last),
+        None if ends_with_consonant_y(last) => format!("{}ies", &last[..last.len() - 1]),
+        None => format!("{}s", last),
+    };
+    if rest.is_empty() {last} else {format!("{} {}", rest, last)}
+}
+
+/// "runs", the present tense with a third person singular subject
+pub(super) fn third_person(verb: &str) -> String {
+    first_word(verb, |verb| match verb {
+        "be" => "is".to_string(),
+        "have" => "has".to_string(),
+        _ if ends_with_sibilant(verb) || verb.ends_with('o') => format!("{}es", verb),
+        _ => suffixed(verb, "s"),
+    })
+}
+
+pub(super) fn past(verb: &str) -> String {
+    first_word(verb, |verb| irregular_verb(verb).map_or_else(|| suffixed(verb, "ed"), |(_, past, _)| past.to_string()))
+}
+
+pub(super) fn past_participle(verb: &str) -> String {
+    first_word(verb, |verb| irregular_verb(verb).map_or_else(|| suffixed(verb, "ed"), |(_, _, participle)| participle.to_string()))
+}
+
+/// "running"
+pub(super) fn present_participle(verb: &str) -> String {
+    first_word(verb, |verb| match verb {
+        _ if verb.ends_with("ie") => format!("{}ying", &verb[..verb.len() - 2]),
+        "be" => "being".to_string(),
+        _ => suffixed(verb, "ing"),
+    })
+}
+
+/// "runner", someone who does it
+pub(super) fn agent_noun(verb: &str) -> String {
+    first_word(verb, |verb| suffixed(verb, "er"))
+}
+
+/// "warmly", how an adjective modifies another
+pub(super) fn adverb(adjective: &str) -> String {
+    if ends_with_consonant_y(adjective) {
+        format!("{}ily", &adjective[..adjective.len() - 1])
+    } else {
+        format!("{}ly", adjective)
+    }
+}
+
+/// "a" or "an", whichever goes before `word`
+pub(super) fn indefinite_article(word: &str) -> &'static str {
+    if word.starts_with(is_vowel) {"an"} else {"a"}
+}
+
+/// like "three hundred twenty-one"
+pub(super) fn number_words(n: u64) -> String {
+    const ONES: [&str; 20] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+        "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+    ];
+    const TENS: [&str; 10] = ["", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+    const SCALES: [(u64, &str); 6] = [
+        (1_000_000_000_000_000_000, "quintillion"), (1_000_000_000_000_000, "quadrillion"), (1_000_000_000_000, "trillion"),
+        (1_000_000_000, "billion"), (1_000_000, "million"), (1_000, "thousand"),
+    ];
+
+    fn below_thousand(n: u64) -> String {
+        match n {
+            0..20 => ONES[n as usize].to_string(),
+            20..100 if n.is_multiple_of(10) => TENS[n as usize / 10].to_string(),
+            20..100 => format!("{}-{}", TENS[n as usize / 10], ONES[n as usize % 10]),
+            _ if n.is_multiple_of(100) => format!("{} hundred", ONES[n as usize / 100]),
+            _ => format!("{} hundred {}", ONES[n as usize / 100], below_thousand(n % 100)),
+        }
+    }
+
+    let mut words = vec![];
+    let mut rest = n;
+    for (scale, name) in SCALES {
+        if rest >= scale {
+            words.push(format!("{} {}", below_thousand(rest / scale), name));
+            rest %= scale;
+        }
+    }
+    if rest > 0 || words.is_empty() {
+        words.push(below_thousand(rest));
+    }
+    words.join(" ")
+}
+
+/// what an english word can be an inflection of
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Inflection {
+    Plain,
+    /// a plural noun or a verb with a third person singular subject
+    S,
+    /// the past tense, or a participle like "run" in "have run"
+    Past,
+    Ing,
+}
+
+/// every word `word` could be an inflection of and how, the word itself first,
+/// guesses like "sleep" for "sleeps" and "slee" for "sleed" included, it's up to the caller to look them up
+pub(super) fn uninflect(word: &str) -> Vec<(String, Inflection)> {
+    let mut forms = vec![(word.to_string(), Inflection::Plain)];
+    forms.extend(IRREGULAR_NOUNS.iter().filter(|(_, plural)| *plural == word).map(|(singular, _)| (singular.to_string(), Inflection::S)));
+    forms.extend(IRREGULAR_VERBS.iter()
+        .filter(|(base, past, participle)| *base != word && (*past == word || *participle == word))
+        .map(|(base, _, _)| (base.to_string(), Inflection::Past)));
+    // "ran" and "run" are both irregular, but "run" is the plain form too
+    forms.extend(IRREGULAR_VERBS.iter()
+        .filter(|(base, _, participle)| *base == word && *participle == word)
+        .map(|(base, _, _)| (base.to_string(), Inflection::Past)));
+
+    // a stem, the stem with a silent e back, and the stem with a doubled consonant undone
+    let stems = |stem: &str| {
+        let mut stems = vec![stem.to_string(), format!("{}e", stem)];
+        let chars: Vec<char> = stem.chars().collect();
+        if let [.., a, b] = chars[..] && a == b && !is_vowel(a) {
+            stems.push(stem[..stem.len() - b.len_utf8()].to_string());
+        }
+        stems
+    };
+    for (ending, inflection) in [("s", Inflection::S), ("ed", Inflection::Past), ("ing", Inflection::Ing)] {
+        let Some(stem) = word.strip_suffix(ending).filter(|s| s.len() > 1) else {continue};
+        if inflection == Inflection::S && stem.ends_with('s') && !stem.ends_with("es") {continue}
+        forms.extend(stems(stem).into_iter().map(|s| (s, inflection)));
+        // "carries", "carried" and "dying"
+        let y = match ending {
+            "s" => stem.strip_suffix("ie").map(|s| format!("{}y", s)),
+            "ed" => stem.strip_suffix('i').map(|s| format!("{}y", s)),
+            _ => stem.strip_suffix('y').map(|s| format!("{}ie", s)),
+        };
+        forms.extend(y.map(|s| (s, inflection)));
+        if let Some(stem) = stem.strip_suffix('e').filter(|_| ending == "s") {
+            forms.push((stem.to_string(), inflection));
+        }
+    }
+    forms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lemmas(word: &str) -> Vec<String> {
+        uninflect(word).into_iter().map(|(lemma, _)| lemma).collect()
+    }
+
+    #[test]
+    fn uninflect_undoes_endings() {
+        assert!(uninflect("sleeps").contains(&("sleep".to_string(), Inflection::S)));
+        assert!(uninflect("running").contains(&("run".to_string(), Inflection::Ing)));
+        assert!(uninflect("carried").contains(&("carry".to_string(), Inflection::Past)));
+        assert!(uninflect("dying").contains(&("die".to_string(), Inflection::Ing)));
+        assert!(uninflect("ran").contains(&("run".to_string(), Inflection::Past)));
+        assert!(uninflect("mice").contains(&("mouse".to_string(), Inflection::S)));
+        assert_eq!(uninflect("cat"), vec![("cat".to_string(), Inflection::Plain)]);
+    }
+
+    #[test]
+    fn uninflect_takes_non_ascii_words() {
+        for word in "the café is ßleeßßing über naïve façades".split(' ') {
+            uninflect(word);
+        }
+        assert!(lemmas("ßleeßßing").contains(&"ßleeß".to_string()));
+        assert!(lemmas("façades").contains(&"façade".to_string()));
+    }
+
+    #[test]
+    fn plurals() {
+        let plurals: Vec<String> = ["bus", "box", "church", "city", "day", "child", "leaf", "fire truck"].map(plural_noun).to_vec();
+        assert_eq!(plurals, ["buses", "boxes", "churches", "cities", "days", "children", "leaves", "fire trucks"]);
+    }
+
+    #[test]
+    fn verb_endings() {
+        let pasts: Vec<String> = ["walk", "stop", "carry", "play", "like", "free", "visit", "run", "fall asleep"].map(past).to_vec();
+        assert_eq!(pasts, ["walked", "stopped", "carried", "played", "liked", "freed", "visited", "ran", "fell asleep"]);
+        let participles: Vec<String> = ["run", "die", "make", "see", "be", "play", "free", "open", "fall asleep"].map(present_participle).to_vec();
+        assert_eq!(participles, ["running", "dying", "making", "seeing", "being", "playing", "freeing", "opening", "falling asleep"]);
+        assert_eq!(third_person("fix"), "fixes");
+        assert_eq!(past_participle("write"), "written");
+        // a doubled letter that isn't ascii
+        assert_eq!(past("faß"), "faßßed");
+    }
+}
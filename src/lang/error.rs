@@ -0,0 +1,93 @@
+use std::fmt;
+
+/// a location in the original markup text
+///
+/// `start` and `end` are byte offsets (so `&text[span.start..span.end]` is the offending bit),
+/// `line` and `column` are 1-based and point at `start`, counted in characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkupErrorKind {
+    UnknownKeyword,
+    MissingParameter,
+    UnexpectedParameter,
+    InvalidParameter,
+    InvalidDeixis,
+    /// a `$name` parameter with nothing to fill it in
+    UnknownVariable,
+    ParametersWithoutKeyword,
+    UnmatchedClosingBrace,
+    UnclosedBrace,
+    NestedBrace,
+    UnexpectedCharacter,
+    /// parsed fine, but the language has no form for it
+    Unrenderable,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkupError {
+    pub kind: MarkupErrorKind,
+    pub span: Span,
+    pub message: String,
+    pub hint: String,
+}
+
+impl MarkupError {
+    pub fn new<M, H>(kind: MarkupErrorKind, span: Span, message: M, hint: H) -> MarkupError
+    where
+        M: Into<String>,
+        H: Into<String>,
+    {
+        MarkupError {
+            kind,
+            span,
+            message: message.into(),
+            hint: hint.into(),
+        }
+    }
+}
+
+impl fmt::Display for MarkupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {} (hint: {})",
+            self.span.line, self.span.column, self.message, self.hint
+        )
+    }
+}
+
+impl std::error::Error for MarkupError {}
+
+/// a problem with one of the language files, found when the language is loaded
+#[derive(Debug, Clone, PartialEq)]
+pub struct LangError {
+    /// e.g. "forms.yaml"
+    pub file: String,
+    /// dotted key path like "root_form.2.verbal", empty when the problem is with the whole file
+    pub path: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for LangError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.file)?;
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        write!(f, "{}", self.message)?;
+        if let Some(line) = self.line {
+            write!(f, " (line {})", line)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for LangError {}
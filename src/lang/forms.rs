@@ -0,0 +1,1116 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::*;
+
+/// the whole of forms.yaml
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Forms {
+    /// molds keyed by how many consonants the root has ("1", "2", ...),
+    /// plus optionally "n" for roots of any length that has no molds of its own
+    pub root_form: BTreeMap<String, RootForms>,
+    pub article: Articles,
+    pub deictic_nouns: DeicticNouns,
+    pub verb_tense_endings: TenseEndings,
+    pub verb_infinitive_suffix: String,
+    /// for orders, `verb{run imp}`
+    #[serde(default)]
+    pub verb_imperative_suffix: String,
+    /// how `verb{run pres neg}` is marked, verbs can't be negated without it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub negation: Option<Marker>,
+    /// how a yes or no question like `verb{run pres q}` is marked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub question: Option<Marker>,
+    /// words for `wh{who}` and the like, keyed by what they ask about
+    #[serde(default)]
+    pub question_words: BTreeMap<String, String>,
+    pub noun_plurality_suffix: String,
+    /// for `nom{eye du}`, languages without one use the plural for two as well
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noun_dual_suffix: Option<String>,
+    /// for `nom{cat pc}`, a few of something
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noun_paucal_suffix: Option<String>,
+    /// keyed by the case as written in markup, e.g. "acc", nouns left nominative take nothing unless "nom" is here too
+    #[serde(default)]
+    pub noun_cases: BTreeMap<String, CaseForm>,
+    /// whether adjectives right after a noun take its case ending as well
+    #[serde(default)]
+    pub adjective_case_agreement: bool,
+    pub verb_particles: VerbParticles,
+    /// endings for verbs agreeing with their subject, keyed like "1sg" or "3pl", see `person_key`
+    #[serde(default)]
+    pub verb_agreement: BTreeMap<String, String>,
+    /// personal pronouns keyed like "1sg", optionally with clusivity or formality after a dot,
+    /// like "1pl.incl" or "2sg.form", anything missing falls back on the plain person and number
+    #[serde(default)]
+    pub pronouns: BTreeMap<String, String>,
+    #[serde(default)]
+    pub phonology: PhonologicalRules,
+    /// how `num{37}` is spelled out, the language can't count without it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub numerals: Option<Numerals>,
+    /// ways of making new words out of a root, used in markup like `nom{run>agent 0}`
+    #[serde(default)]
+    pub derivations: BTreeMap<String, Derivation>,
+    /// how roots are put together in markup like `nom{fire+water 0}`, the language has no compounds without it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compounding: Option<Compounding>,
+    /// markup keywords declared by the language on top of the built in ones
+    #[serde(default)]
+    pub keywords: KeywordDefinitions,
+    /// what the grammar checker holds sentences to, see `Lang::check`
+    #[serde(default)]
+    pub grammar: GrammarRules,
+    /// how the canonical order markup is written in gets rearranged, see `Lang::word_order`
+    #[serde(default)]
+    pub word_order: WordOrder,
+}
+
+/// one mold per part of speech, each `-` is filled with the next root consonant,
+/// and named slots like `{C2}` or `{V1}` pick a specific one, see `Mold`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RootForms {
+    pub adjective: String,
+    pub nominative: String,
+    pub verbal: String,
+    pub verbal_adjective: String,
+    pub prepositional: String,
+    pub adject_adjective: String,
+    /// molds for parts of speech only used by keywords from `keywords`, e.g. `gerund: "-e-ul"`
+    #[serde(flatten)]
+    pub other: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Articles {
+    pub definite: ArticleSet,
+    pub indefinite: ArticleSet,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArticleSet {
+    pub non_spatial: String,
+    pub proximal: String,
+    pub immediate: String,
+    pub distal: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeicticNouns {
+    pub spatial: DeicticNounSet,
+    pub temporal: DeicticNounSet,
+}
+
+/// like `ArticleSet`, but deictic nouns always point somewhere so there is no non spatial form
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeicticNounSet {
+    pub proximal: String,
+    pub immediate: String,
+    pub distal: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TenseEndings {
+    pub future: String,
+    pub present: String,
+    pub past: String,
+}
+
+/// how a noun is put in a case, an ending after the plural one, a mold in place of the nominative mold, or both
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CaseForm {
+    #[serde(default)]
+    pub suffix: String,
+    /// a `root_form` mold, e.g. `locative` alongside `nominative`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mold: Option<String>,
+}
+
+/// an affix on the verb or a particle next to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Marker {
+    pub form: String,
+    pub placement: MarkerPlacement,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkerPlacement {
+    Prefix,
+    Suffix,
+    /// a word of its own right before the verb
+    Before,
+    /// a word of its own right after the verb
+    After,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VerbParticles {
+    pub aspect: AspectParticles,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AspectParticles {
+    pub completive: String,
+    pub progressive: String,
+    pub habitual: String,
+    pub perfect: String,
+}
+
+/// a new word made from a root, by filling a mold of its own in place of the keyword's, by adding a suffix, or both
+///
+/// ```yaml
+/// agent: { mold: agent, gloss: AGT, keywords: [nom], from: [verb] }
+/// dim: { suffix: "ito", gloss: DIM }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Derivation {
+    /// a `root_form` mold, the derived stem is glossed as `root.GLOSS` instead of by its keyword
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mold: Option<String>,
+    /// added after the stem, before any inflection
+    #[serde(default)]
+    pub suffix: String,
+    pub gloss: String,
+    /// the keywords it can be used with, like `nom` for an agent noun, any keyword taking a root if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    /// what a root has to be usable as to take it, like `verb` for an agent noun, see `Root::keywords`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub from: Vec<String>,
+}
+
+/// the last root of a compound (or the first, see `head`) is the one the keyword's mold and endings go on,
+/// the others go in `mold` and are glossed by just the root
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Compounding {
+    /// a `root_form` mold for the roots besides the head, their nominative mold if left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mold: Option<String>,
+    /// the head `after` the other roots like english "firewater", or `before` them like "water-of-fire"
+    pub head: Placement,
+    /// put between roots
+    #[serde(default)]
+    pub link: String,
+}
+
+/// a number is said as a sum of digits times powers of the base, each term made of the word for the digit
+/// and the word for the power, like "three ten seven" for 37, put together into one word
+///
+/// ```yaml
+/// base: decimal
+/// digits: [nul, un, du, tri, kar, pen, ses, sep, ok, nau]
+/// powers: [dek, sen, mil]
+/// omit_one: true
+/// link: "e"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Numerals {
+    pub base: NumeralBase,
+    /// a word for every digit, from zero up to one less than the base
+    pub digits: Vec<String>,
+    /// a word for every power of the base the language has, starting with the base itself,
+    /// e.g. ten, hundred and thousand, anything past the last one can't be said
+    pub powers: Vec<String>,
+    /// the digit counting a power before it, "three ten", or after it, "ten three"
+    #[serde(default = "default_before")]
+    pub multiplier: Placement,
+    /// say just "ten" rather than "one ten"
+    #[serde(default)]
+    pub omit_one: bool,
+    /// `descending` starts with the biggest power, `ascending` with the ones, like "seven and thirty"
+    #[serde(default)]
+    pub order: NumeralOrder,
+    /// put between terms, e.g. "e" for "thirty and seven"
+    #[serde(default)]
+    pub link: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumeralBase {
+    Decimal,
+    Duodecimal,
+    Vigesimal,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumeralOrder {
+    #[default]
+    Descending,
+    Ascending,
+}
+
+fn default_before() -> Placement {
+    Placement::Before
+}
+
+/// sound changes applied after the morphemes of a sentence are put together, see `RuleSet`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PhonologicalRules {
+    /// named groups of segments rules can refer to as `{name}`, e.g. `V: [a, e, i, o, u]`
+    #[serde(default)]
+    pub classes: BTreeMap<String, Vec<String>>,
+    /// applied in order, each either `harmony` or like `a+a -> a` or `n -> m / _{labial}`
+    #[serde(default)]
+    pub rules: Vec<String>,
+    /// sets of vowels that agree with each other, members correspond by position,
+    /// e.g. `front: [e, i]` and `back: [a, u]`
+    #[serde(default)]
+    pub harmony: BTreeMap<String, Vec<String>>,
+}
+
+/// a keyword like `evid{seen}`, rendered as a stem or particle followed by one ending per parameter
+///
+/// ```yaml
+/// evid:
+///   form: "su"
+///   gloss: EVID
+///   parameters:
+///     - name: source
+///       values:
+///         seen: { gloss: VIS }
+///         heard: { form: "ku", gloss: AUD }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KeywordDefinition {
+    /// the `root_form` mold the root goes into, keywords with one take a root as their first parameter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mold: Option<String>,
+    /// the particle itself, for keywords without a mold
+    #[serde(default)]
+    pub form: String,
+    /// read `form` from somewhere else in forms.yaml instead, e.g. `verb_particles.aspect.perfect`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// label for the stem or particle, a stem is glossed as `root.GLOSS`
+    pub gloss: String,
+    #[serde(default)]
+    pub parameters: Vec<ParameterDefinition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParameterDefinition {
+    pub name: String,
+    /// every value the parameter may take, along with the ending it adds
+    pub values: BTreeMap<String, Affix>,
+}
+
+/// an ending added by a parameter value, an empty one only shows up in the gloss
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Affix {
+    #[serde(default)]
+    pub form: String,
+    /// like `KeywordDefinition::path`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub gloss: String,
+}
+
+pub type KeywordDefinitions = BTreeMap<String, KeywordDefinition>;
+
+/// ordering and agreement constraints on sentences, given in terms of keyword categories
+/// (adjective, noun, pronoun, verb, preposition, aspect, article, question, numeral, or the name of a declared keyword)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GrammarRules {
+    /// e.g. `article: [noun]`, every article needs a noun after it in the same phrase
+    #[serde(default)]
+    pub must_precede: BTreeMap<String, Vec<String>>,
+    /// e.g. `adjective: [noun]`, every adjective needs a noun before it in the same phrase
+    #[serde(default)]
+    pub must_follow: BTreeMap<String, Vec<String>>,
+    /// categories every sentence needs at least one of
+    #[serde(default)]
+    pub required: Vec<String>,
+    /// whether a verb given a person and number has to match its subject
+    #[serde(default)]
+    pub subject_verb_agreement: bool,
+}
+
+/// where one part of a phrase goes relative to the rest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Placement {
+    Before,
+    After,
+}
+
+/// anything left out keeps the order the markup was written in
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WordOrder {
+    /// the order of subject, object and verb, e.g. `sov` or `vso`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clause: Option<String>,
+    /// `before` its noun phrase for prepositions, `after` for postpositions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adpositions: Option<Placement>,
+    /// adjectives relative to their noun
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adjectives: Option<Placement>,
+    /// articles relative to the rest of their noun phrase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub articles: Option<Placement>,
+    /// numerals relative to the rest of their noun phrase, after the articles
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub numerals: Option<Placement>,
+}
+
+/// root keys mapped to their consonants, e.g. `water: wd`,
+/// optionally followed by a vowel melody for `{V1}` style slots, e.g. `write: ktb/ai`
+pub type Roots = BTreeMap<String, Root>;
+
+/// one entry of roots.yaml, written as just its consonants unless there's more to say about it
+///
+/// ```yaml
+/// be:
+///   consonants: p
+///   gloss: to be, to exist
+///   keywords: [verb]
+///   field: existence
+///   irregular:
+///     verbal.past: "wos"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RootEntry", into = "RootEntry")]
+pub struct Root {
+    pub consonants: String,
+    /// what it means in english, alternatives separated by commas, the key itself if left out
+    pub gloss: String,
+    /// the keywords it can be used with, like `nom` or `verb`, any keyword taking a root if empty
+    pub keywords: Vec<String>,
+    /// what sort of thing it's about, like "animals" or "weather"
+    pub field: Option<String>,
+    pub notes: Option<String>,
+    /// markup using it
+    pub examples: Vec<String>,
+    /// words used instead of filling a mold, keyed by the mold like `verbal`,
+    /// or by the mold and an ending they stand in for as well, like `verbal.past` or `nominative.pl`
+    pub irregular: BTreeMap<String, String>,
+    /// the english to translate it as when used with one keyword in particular, like `prep: toward`,
+    /// for when the first fitting word of its gloss doesn't do
+    pub senses: BTreeMap<String, String>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum RootEntry {
+    Consonants(String),
+    Full(RootDefinition),
+}
+
+// by hand rather than untagged, so a typo in a map says what's wrong with it
+impl<'de> Deserialize<'de> for RootEntry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<RootEntry, D::Error> {
+        match serde_yaml::Value::deserialize(deserializer)? {
+            serde_yaml::Value::String(consonants) => Ok(RootEntry::Consonants(consonants)),
+            value => serde_yaml::from_value(value).map(RootEntry::Full).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RootDefinition {
+    consonants: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    gloss: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    keywords: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    field: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    examples: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    irregular: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    senses: BTreeMap<String, String>,
+}
+
+impl From<RootEntry> for Root {
+    fn from(entry: RootEntry) -> Root {
+        match entry {
+            RootEntry::Consonants(consonants) => Root::new(consonants),
+            RootEntry::Full(d) => Root {
+                consonants: d.consonants,
+                gloss: d.gloss,
+                keywords: d.keywords,
+                field: d.field,
+                notes: d.notes,
+                examples: d.examples,
+                irregular: d.irregular,
+                senses: d.senses,
+            },
+        }
+    }
+}
+
+impl From<Root> for RootEntry {
+    fn from(root: Root) -> RootEntry {
+        if root == Root::new(root.consonants.clone()) {
+            return RootEntry::Consonants(root.consonants);
+        }
+        RootEntry::Full(RootDefinition {
+            consonants: root.consonants,
+            gloss: root.gloss,
+            keywords: root.keywords,
+            field: root.field,
+            notes: root.notes,
+            examples: root.examples,
+            irregular: root.irregular,
+            senses: root.senses,
+        })
+    }
+}
+
+impl Root {
+    pub fn new(consonants: String) -> Root {
+        Root { consonants, ..Root::default() }
+    }
+
+    /// whether it can be used with `keyword`, like `nom`
+    pub fn allows(&self, keyword: &str) -> bool {
+        self.keywords.is_empty() || self.keywords.iter().any(|k| k == keyword)
+    }
+
+    /// the irregular form for a mold, or for a mold with an inflection like "past" or "pl" on top
+    pub fn irregular(&self, part: &str, inflection: Option<&str>) -> Option<&String> {
+        match inflection {
+            Some(inflection) => self.irregular.get(&format!("{}.{}", part, inflection)),
+            None => self.irregular.get(part),
+        }
+    }
+}
+
+impl RootForms {
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &String)> {
+        [
+            ("adjective", &self.adjective),
+            ("nominative", &self.nominative),
+            ("verbal", &self.verbal),
+            ("verbal_adjective", &self.verbal_adjective),
+            ("prepositional", &self.prepositional),
+            ("adject_adjective", &self.adject_adjective),
+        ].into_iter().chain(self.other.iter().map(|(k, v)| (k.as_str(), v)))
+    }
+
+    /// the mold for a part of speech by its name in forms.yaml
+    pub fn get(&self, part: &str) -> Option<&String> {
+        self.iter().find(|(p, _)| *p == part).map(|(_, mold)| mold)
+    }
+}
+
+impl ArticleSet {
+    pub fn get(&self, deixis: &Deixis) -> &str {
+        match deixis {
+            Deixis::NonSpatial => &self.non_spatial,
+            Deixis::Proximal => &self.proximal,
+            Deixis::Immediate => &self.immediate,
+            Deixis::Distal => &self.distal,
+        }
+    }
+}
+
+impl DeicticNounSet {
+    /// `None` for non spatial deixis, which the markup parser never lets through for deictic nouns
+    pub fn get(&self, deixis: &Deixis) -> Option<&str> {
+        match deixis {
+            Deixis::NonSpatial => None,
+            Deixis::Proximal => Some(&self.proximal),
+            Deixis::Immediate => Some(&self.immediate),
+            Deixis::Distal => Some(&self.distal),
+        }
+    }
+}
+
+/// finds the line a key path like `["root_form", "2", "verbal"]` is written on
+///
+/// this only understands block style mappings, a key inside a `{ ... }` gets the line of the
+/// closest key around it that is written in block style
+pub(super) fn line_of(text: &str, path: &[&str]) -> Option<usize> {
+    let mut stack: Vec<(usize, String)> = vec![];
+    let mut closest: Option<(usize, usize)> = None;
+    for (i, line) in text.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {continue}
+        let indent = line.len() - trimmed.len();
+        let key = match trimmed.split_once(':') {
+            Some((key, _)) => key.trim().trim_matches(|c| c == '"' || c == '\'').to_string(),
+            None => continue,
+        };
+
+        while stack.last().is_some_and(|(i, _)| *i >= indent) {
+            stack.pop();
+        }
+        stack.push((indent, key));
+
+        if stack.len() <= path.len() && stack.iter().zip(path).all(|((_, a), b)| a == b) {
+            if stack.len() == path.len() {
+                return Some(i + 1);
+            }
+            if closest.is_none_or(|(depth, _)| stack.len() > depth) {
+                closest = Some((stack.len(), i + 1));
+            }
+        }
+    }
+    closest.map(|(_, line)| line)
+}
+
+fn parse_error(file: &str, e: serde_yaml::Error) -> LangError {
+    let line = e.location().map(|l| l.line());
+    let mut message = e.to_string();
+    if let Some(l) = e.location() {
+        let suffix = format!(" at line {} column {}", l.line(), l.column());
+        if let Some(stripped) = message.strip_suffix(&suffix) {message = stripped.to_string()}
+    }
+    // serde_yaml puts the key path first when it knows it, e.g. "article.definite: missing field `distal`"
+    let (path, message) = match message.split_once(": ") {
+        Some((path, rest)) if !path.contains(' ') => (path.to_string(), rest.to_string()),
+        _ => (String::new(), message),
+    };
+    LangError { file: file.to_string(), path, line, message }
+}
+
+/// the `pronouns` or `verb_agreement` key for a person and number, e.g. "1pl" or "1pl.incl.form"
+pub fn person_key(person: &Person, number: &Number, clusivity: Option<&Clusivity>, formality: Option<&Formality>) -> String {
+    let mut key = format!("{}{}", person.as_str(), number.as_str());
+    for feature in [clusivity.map(|c| c.as_str()), formality.map(|f| f.as_str())].into_iter().flatten() {
+        key.push('.');
+        key.push_str(feature);
+    }
+    key
+}
+
+/// reads a key written by `person_key` back, `None` if it isn't one
+pub fn parse_person_key(key: &str) -> Option<(Person, Number, Option<Clusivity>, Option<Formality>)> {
+    let mut pieces = key.split('.');
+    let base = pieces.next()?;
+    let person = Person::ALL.into_iter().find(|p| base.starts_with(p.as_str()))?;
+    let number = Number::ALL.into_iter().find(|n| base[person.as_str().len()..] == *n.as_str())?;
+    let (mut clusivity, mut formality) = (None, None);
+    for piece in pieces {
+        match piece {
+            "incl" if clusivity.is_none() => clusivity = Some(Clusivity::Inclusive),
+            "excl" if clusivity.is_none() => clusivity = Some(Clusivity::Exclusive),
+            "fam" if formality.is_none() => formality = Some(Formality::Familiar),
+            "form" if formality.is_none() => formality = Some(Formality::Formal),
+            _ => return None,
+        }
+    }
+    // canonical order only, so each pronoun has exactly one key
+    let parsed = (person, number, clusivity, formality);
+    (person_key(&parsed.0, &parsed.1, parsed.2.as_ref(), parsed.3.as_ref()) == key).then_some(parsed)
+}
+
+/// looks up a dotted key path like `verb_particles.aspect.perfect`, which has to lead to a string
+fn resolve_path(forms: &serde_yaml::Value, path: &str) -> Option<String> {
+    let mut value = forms;
+    for key in path.split('.') {
+        value = value.get(key)?;
+    }
+    value.as_str().map(|s| s.to_string())
+}
+
+/// the `root_form` key for molds used by any root length that has none of its own
+pub const FALLBACK_MOLDS: &str = "n";
+
+impl Forms {
+    /// the molds for roots of `length` consonants, falling back on the "n" molds, along with the key they're under
+    pub fn molds_for(&self, length: usize) -> Option<(&str, &RootForms)> {
+        self.root_form.get_key_value(&length.to_string())
+            .or_else(|| self.root_form.get_key_value(FALLBACK_MOLDS))
+            .map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// the markup keywords that take a root, built in or declared with a mold
+    pub fn root_keywords(&self) -> Vec<&str> {
+        ["adj", "nom", "verb", "vadj", "prep", "aadj"].into_iter()
+            .chain(self.keywords.iter().filter(|(_, d)| d.mold.is_some()).map(|(k, _)| k.as_str()))
+            .collect()
+    }
+
+    /// the most specific pronoun the language has for these features, along with the features it actually marks
+    pub fn pronoun<'a>(&'a self, person: &Person, number: &Number, clusivity: Option<&'a Clusivity>, formality: Option<&'a Formality>)
+        -> Option<(&'a str, Option<&'a Clusivity>, Option<&'a Formality>)>
+    {
+        [(clusivity, formality), (clusivity, None), (None, formality), (None, None)].into_iter()
+            .find_map(|(c, f)| self.pronouns.get(&person_key(person, number, c, f)).map(|form| (form.as_str(), c, f)))
+    }
+
+    /// parses and checks forms.yaml, `roots` is used to make sure every root has molds to go in
+    pub fn from_yaml(text: &str, roots: &Roots) -> Result<Forms, LangError> {
+        let mut forms: Forms = serde_yaml::from_str(text).map_err(|e| parse_error("forms.yaml", e))?;
+
+        let error = |path: &[&str], message: String| LangError {
+            file: "forms.yaml".to_string(),
+            path: path.join("."),
+            line: line_of(text, path),
+            message,
+        };
+
+        for (length, molds) in &forms.root_form {
+            let slots = match length.parse::<usize>() {
+                Ok(n) if n > 0 => Some(n),
+                _ if length == FALLBACK_MOLDS => None,
+                _ => return Err(error(&["root_form", length], format!("`{}` is not a consonant count or `{}`", length, FALLBACK_MOLDS))),
+            };
+            for (part, mold) in molds.iter() {
+                let parsed = Mold::parse(mold).map_err(|e| error(&["root_form", length, part], e))?;
+                let found = parsed.anonymous_slots();
+                // molds made only of `-` have to use the whole root, named slots may drop consonants
+                let mismatched = match slots {
+                    Some(n) if parsed.is_anonymous() => found != n,
+                    Some(n) => found > n,
+                    None => false,
+                };
+                if mismatched {
+                    return Err(error(
+                        &["root_form", length, part],
+                        format!("mold `{}` has {} slot(s) but is listed under roots of {} consonant(s)", mold, found, length),
+                    ));
+                }
+            }
+        }
+
+        let sets: Vec<&Vec<String>> = forms.phonology.harmony.values().collect();
+        if sets.windows(2).any(|w| w[0].len() != w[1].len()) {
+            return Err(error(&["phonology", "harmony"], "every harmony set needs the same number of vowels".to_string()));
+        }
+        if let Err((i, e)) = RuleSet::new(&forms.phonology) {
+            return Err(error(&["phonology", "rules"], format!("rule {}: {}", i + 1, e)));
+        }
+
+        forms.check_references().map_err(|(path, message)| error(&path.iter().map(|p| p.as_str()).collect::<Vec<&str>>(), message))?;
+
+        for (root, entry) in roots {
+            let consonants = &entry.consonants;
+            let length = consonant_count(consonants);
+            let (key, molds) = forms.molds_for(length).ok_or_else(|| error(
+                &["root_form"],
+                format!("root `{}` has {} consonant(s) but there are no molds for that length or `{}` molds", root, length, FALLBACK_MOLDS),
+            ))?;
+            // named slots can ask for more than a root has, which only shows up once they're filled
+            for (part, mold) in molds.iter() {
+                Mold::parse(mold).and_then(|m| m.fill(consonants)).map_err(|e| error(
+                    &["root_form", key, part],
+                    format!("can't fill mold `{}` with root `{}` ({}): {}", mold, root, consonants, e),
+                ))?;
+            }
+            for form in entry.irregular.keys() {
+                let part = form.split('.').next().unwrap_or(form);
+                if molds.get(part).is_none() {
+                    return Err(error(
+                        &["root_form", key],
+                        format!("root `{}` has an irregular `{}` but there's no `{}` mold for it to stand in for", root, form, part),
+                    ));
+                }
+            }
+        }
+
+        Ok(forms)
+    }
+
+    /// makes sure everything the sections after the molds refer to exists, like the cases of `noun_cases`
+    /// or the molds and `path`s of `keywords`, filling the paths in, errors come with the key path they're about
+    fn check_references(&mut self) -> Result<(), (Vec<String>, String)> {
+        let is_symbol = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        let at = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<Vec<String>>();
+        let value = serde_yaml::to_value(&*self).map_err(|e| (vec![], e.to_string()))?;
+        let resolve = |path: &Option<String>, form: &mut String, keys: &[&str]| -> Result<(), (Vec<String>, String)> {
+            if let Some(path) = path {
+                *form = resolve_path(&value, path).ok_or((at(keys), format!("`{}` doesn't lead to a form in forms.yaml", path)))?;
+            }
+            Ok(())
+        };
+
+        for (name, definition) in &mut self.keywords {
+            if KEYWORDS.contains(&name.as_str()) {
+                return Err((at(&["keywords", name]), format!("`{}` is already a built in keyword", name)));
+            }
+            if !is_symbol(name) {
+                return Err((at(&["keywords", name]), "keyword names may only contain letters, digits, `-` and `_`".to_string()));
+            }
+            if let Some(mold) = &definition.mold {
+                if definition.path.is_some() || !definition.form.is_empty() {
+                    return Err((at(&["keywords", name]), "a keyword takes either a `mold` or a `form`, not both".to_string()));
+                }
+                if let Some((length, _)) = self.root_form.iter().find(|(_, molds)| molds.get(mold).is_none()) {
+                    return Err((at(&["keywords", name, "mold"]), format!("no `{}` mold for roots of length {}", mold, length)));
+                }
+            }
+            resolve(&definition.path, &mut definition.form, &["keywords", name, "path"])?;
+
+            for parameter in &mut definition.parameters {
+                if parameter.values.is_empty() {
+                    return Err((at(&["keywords", name, "parameters"]), format!("parameter `{}` has no values", parameter.name)));
+                }
+                for (value, affix) in &mut parameter.values {
+                    if !is_symbol(value) {
+                        return Err((at(&["keywords", name, "parameters"]), format!("value `{}` of `{}` can't be written in markup", value, parameter.name)));
+                    }
+                    resolve(&affix.path, &mut affix.form, &["keywords", name, "parameters"])?;
+                }
+            }
+        }
+
+        for (table, entries) in [("pronouns", &self.pronouns), ("verb_agreement", &self.verb_agreement)] {
+            for key in entries.keys() {
+                match parse_person_key(key) {
+                    Some((_, _, Some(_), _)) if !key.starts_with("1pl") => return Err((
+                        at(&[table, key]),
+                        "only first person plural can be inclusive or exclusive".to_string(),
+                    )),
+                    Some((_, _, c, f)) if table == "verb_agreement" && (c.is_some() || f.is_some()) => return Err((
+                        at(&[table, key]),
+                        "verbs only agree in person and number".to_string(),
+                    )),
+                    Some(_) => (),
+                    None => return Err((
+                        at(&[table, key]),
+                        format!("`{}` should be a person and number like `1sg` or `3pl`, optionally followed by `.incl`, `.excl`, `.fam` or `.form`", key),
+                    )),
+                }
+            }
+            // every person and number needs something, so any subject can be rendered
+            if !entries.is_empty() {
+                for person in &Person::ALL {
+                    for number in &Number::ALL {
+                        let key = person_key(person, number, None, None);
+                        if !entries.contains_key(&key) {
+                            return Err((at(&[table]), format!("missing `{}`", key)));
+                        }
+                    }
+                }
+            }
+        }
+
+        let categories: Vec<&str> = CATEGORIES.iter().copied().chain(self.keywords.keys().map(|k| k.as_str())).collect();
+        let grammar = &self.grammar;
+        let constraints = [("must_precede", &grammar.must_precede), ("must_follow", &grammar.must_follow)];
+        for (section, constraints) in constraints {
+            for (category, others) in constraints {
+                if let Some(unknown) = std::iter::once(category).chain(others).find(|c| !categories.contains(&c.as_str())) {
+                    return Err((at(&["grammar", section, category]), format!("no keyword category `{}`, use one of {}", unknown, categories.join(", "))));
+                }
+            }
+        }
+        if let Some(unknown) = grammar.required.iter().find(|c| !categories.contains(&c.as_str())) {
+            return Err((at(&["grammar", "required"]), format!("no keyword category `{}`, use one of {}", unknown, categories.join(", "))));
+        }
+
+        if let Some(clause) = &self.word_order.clause {
+            let mut letters: Vec<char> = clause.chars().collect();
+            letters.sort();
+            if letters != ['o', 's', 'v'] {
+                return Err((at(&["word_order", "clause"]), format!("`{}` should be an order of s, o and v, like `sov`", clause)));
+            }
+        }
+
+        if let Some(word) = self.question_words.keys().find(|w| !is_symbol(w)) {
+            return Err((at(&["question_words", word]), format!("`{}` can't be written in markup, use letters, digits, `-` and `_`", word)));
+        }
+
+        if let Some(numerals) = &self.numerals {
+            if numerals.digits.len() as u64 != numerals.base.radix() {
+                return Err((at(&["numerals", "digits"]), format!(
+                    "a {} system needs {} digits from zero up, not {}",
+                    numerals.base.as_str(), numerals.base.radix(), numerals.digits.len(),
+                )));
+            }
+            if numerals.powers.is_empty() {
+                return Err((at(&["numerals", "powers"]), "needs at least a word for the base itself".to_string()));
+            }
+        }
+
+        let root_keywords = self.root_keywords();
+        for (name, derivation) in &self.derivations {
+            if !is_symbol(name) {
+                return Err((at(&["derivations", name]), "derivation names may only contain letters, digits, `-` and `_`".to_string()));
+            }
+            if derivation.mold.is_none() && derivation.suffix.is_empty() {
+                return Err((at(&["derivations", name]), "a derivation needs a `mold`, a `suffix` or both".to_string()));
+            }
+            for (field, keywords) in [("keywords", &derivation.keywords), ("from", &derivation.from)] {
+                if let Some(keyword) = keywords.iter().find(|k| !root_keywords.contains(&k.as_str())) {
+                    return Err((at(&["derivations", name, field]), format!("`{}` doesn't take a root, use one of {}", keyword, root_keywords.join(", "))));
+                }
+            }
+        }
+        let molds = self.derivations.iter().filter_map(|(name, d)| d.mold.as_ref().map(|m| (vec!["derivations", name.as_str(), "mold"], m)))
+            .chain(self.compounding.iter().filter_map(|c| c.mold.as_ref().map(|m| (vec!["compounding", "mold"], m))));
+        for (keys, mold) in molds {
+            if let Some((length, _)) = self.root_form.iter().find(|(_, molds)| molds.get(mold).is_none()) {
+                return Err((at(&keys), format!("no `{}` mold for roots of length {}", mold, length)));
+            }
+        }
+
+        for (case, form) in &self.noun_cases {
+            if Case::from_name(case).is_none() {
+                let cases: Vec<&str> = Case::ALL.iter().map(|c| c.as_str()).collect();
+                return Err((at(&["noun_cases", case]), format!("no such case `{}`, use one of {}", case, cases.join(", "))));
+            }
+            let missing = form.mold.as_ref().and_then(|mold| self.root_form.iter().find(|(_, molds)| molds.get(mold).is_none()).map(|(l, _)| (mold, l)));
+            if let Some((mold, length)) = missing {
+                return Err((at(&["noun_cases", case, "mold"]), format!("no `{}` mold for roots of length {}", mold, length)));
+            }
+        }
+
+        // anything else under a root length would be a typo, unless a keyword or case uses it
+        for (length, molds) in &self.root_form {
+            for part in molds.other.keys() {
+                let used = self.keywords.values().any(|d| d.mold.as_ref() == Some(part))
+                    || self.noun_cases.values().any(|c| c.mold.as_ref() == Some(part))
+                    || self.derivations.values().any(|d| d.mold.as_ref() == Some(part))
+                    || self.compounding.as_ref().is_some_and(|c| c.mold.as_ref() == Some(part));
+                if !used {
+                    return Err((at(&["root_form", length, part]), format!("`{}` is not a part of speech and no keyword, case, derivation or compound uses it as a mold", part)));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// parses roots.yaml
+pub fn roots_from_yaml(text: &str) -> Result<Roots, LangError> {
+    let roots: Roots = serde_yaml::from_str(text).map_err(|e| parse_error("roots.yaml", e))?;
+    let error = |path: &[&str], message: String| LangError {
+        file: "roots.yaml".to_string(),
+        path: path.join("."),
+        line: line_of(text, path),
+        message,
+    };
+    // what can follow the mold in an irregular form's key
+    let inflections: Vec<&str> = VerbForm::ALL.iter().map(|f| f.as_str())
+        .chain(["du", "pc", "pl"])
+        .collect();
+    for (name, root) in &roots {
+        if root.consonants.is_empty() {
+            return Err(error(&[name], "root has no consonants".to_string()));
+        }
+        for key in root.irregular.keys() {
+            match key.split_once('.') {
+                Some((_, inflection)) if !inflections.contains(&inflection) => return Err(error(
+                    &[name, "irregular", key],
+                    format!("`{}` is no inflection a whole word can stand in for, use one of {}", inflection, inflections.join(", ")),
+                )),
+                _ => (),
+            }
+        }
+    }
+    Ok(roots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn shipped_roots() -> Roots {
+        roots_from_yaml(&fs::read_to_string("assets/lang/roots.yaml").unwrap()).unwrap()
+    }
+
+    /// the shipped forms.yaml with each `from` replaced by its `to`, and what came of it
+    fn shipped_with(replacements: &[(&str, &str)], roots: &Roots) -> (String, Result<Forms, LangError>) {
+        let mut text = fs::read_to_string("assets/lang/forms.yaml").unwrap();
+        for (from, to) in replacements {
+            assert!(text.contains(from), "{}", from);
+            text = text.replacen(from, to, 1);
+        }
+        let forms = Forms::from_yaml(&text, roots);
+        (text, forms)
+    }
+
+    /// the first line `needle` is on
+    fn line(text: &str, needle: &str) -> Option<usize> {
+        text.lines().position(|l| l.contains(needle)).map(|i| i + 1)
+    }
+
+    /// the shipped forms.yaml with its `keywords` swapped for these
+    fn with_keywords(keywords: &str) -> Result<Forms, LangError> {
+        let text = fs::read_to_string("assets/lang/forms.yaml").unwrap();
+        let (rest, _) = text.split_once("\nkeywords:").unwrap();
+        Forms::from_yaml(&format!("{}\nkeywords:\n{}", rest, keywords), &shipped_roots())
+    }
+
+    const KEYWORDS: &str = "  evid:
+    form: su
+    gloss: EVID
+    parameters:
+      - name: source
+        values:
+          seen: { gloss: VIS }
+          heard: { form: ku, gloss: AUD }
+  ger:
+    mold: verbal_adjective
+    gloss: GER
+    parameters:
+      - name: aspect
+        values:
+          done: { path: verb_particles.aspect.perfect, gloss: PRF }
+";
+
+    #[test]
+    fn declared_keywords_parse_and_render() {
+        let mut lang = Lang::load("assets/lang").unwrap();
+        lang.forms = with_keywords(KEYWORDS).unwrap();
+        lang.roots.get_mut("run").unwrap().keywords.push("ger".to_string());
+        assert_eq!(
+            lang.parse("evid{heard} ger{run done}").unwrap(),
+            vec![
+                Keyword::Defined("evid".to_string(), None, vec!["heard".to_string()]),
+                Keyword::Defined("ger".to_string(), Some("run".to_string()), vec!["done".to_string()]),
+            ],
+        );
+        assert_eq!(lang.render("evid{heard}").unwrap().trim(), "suku");
+        assert_eq!(lang.render("evid{seen}").unwrap().trim(), "su");
+        // a root in the mold, then the ending `path` pointed at
+        let morphemes = lang.parse("ger{run done}").unwrap()[0].morphemes(&lang).unwrap();
+        let forms: Vec<(&str, &str)> = morphemes.iter().map(|m| (m.form.as_str(), m.gloss.as_str())).collect();
+        let stem = Keyword::format_base("verbal_adjective", "run", &lang).unwrap();
+        assert_eq!(forms, vec![(stem.as_str(), "run.GER"), (lang.forms.verb_particles.aspect.perfect.as_str(), "PRF")]);
+
+        let kinds = |markup: &str| lang.parse(markup).unwrap_err().into_iter().map(|e| e.kind).collect::<Vec<_>>();
+        assert_eq!(kinds("evid{smelt}"), vec![MarkupErrorKind::InvalidParameter]);
+        assert_eq!(kinds("evid{}"), vec![MarkupErrorKind::MissingParameter]);
+        assert_eq!(kinds("ger{nosuchroot done}"), vec![MarkupErrorKind::InvalidParameter]);
+        assert_eq!(kinds("said{seen}"), vec![MarkupErrorKind::UnknownKeyword]);
+    }
+
+    #[test]
+    fn bad_keyword_definitions_are_turned_down() {
+        let cases = [
+            ("  nom: { form: su, gloss: X }\n", "keywords.nom", "built in"),
+            ("  \"ev id\": { form: su, gloss: X }\n", "keywords.ev id", "letters, digits"),
+            ("  ger: { mold: verbal, form: su, gloss: X }\n", "keywords.ger", "not both"),
+            ("  ger: { mold: gerund, gloss: X }\n", "keywords.ger.mold", "no `gerund` mold"),
+            ("  evid: { path: verb_particles.aspect.never, gloss: X }\n", "keywords.evid.path", "doesn't lead to a form"),
+            ("  evid: { form: su, gloss: X, parameters: [{ name: source, values: {} }] }\n", "keywords.evid.parameters", "has no values"),
+            ("  evid: { form: su, gloss: X, parameters: [{ name: source, values: { \"wa}\": { gloss: Y } } }] }\n", "keywords.evid.parameters", "can't be written"),
+            ("  evid: { form: su, gloss: X, color: red }\n", "", "unknown field `color`"),
+        ];
+        for (keywords, path, message) in cases {
+            let error = with_keywords(keywords).unwrap_err();
+            assert!(error.path.ends_with(path) && error.message.contains(message), "{}: {}", keywords, error);
+        }
+    }
+
+    #[test]
+    fn references_have_to_lead_somewhere() {
+        let roots = shipped_roots();
+        let cases = [
+            ("  acc: { suffix: \"ku\" }", "  acc: { suffix: \"ku\", mold: accusative }", "mold: accusative", "noun_cases.acc.mold", "no `accusative` mold"),
+            ("  loc: { suffix: \"ne\" }", "  locative: { suffix: \"ne\" }", "locative:", "noun_cases.locative", "no such case `locative`"),
+            ("{ mold: agent, gloss: AGT", "{ mold: agentive, gloss: AGT", "mold: agentive", "derivations.agent.mold", "no `agentive` mold"),
+            ("    adject_adjective: \"-ra-e-el\"", "    adject_adjective: \"-ra-e-el\"\n    gerund: \"-e-ul-\"", "gerund", "root_form.3.gerund", "no keyword, case, derivation or compound uses it"),
+        ];
+        for (from, to, on, path, message) in cases {
+            let (text, forms) = shipped_with(&[(from, to)], &roots);
+            let error = forms.unwrap_err();
+            assert_eq!((error.file.as_str(), error.path.as_str(), error.line), ("forms.yaml", path, line(&text, on)), "{}", error);
+            assert!(error.message.contains(message), "{}", error);
+        }
+        let (text, forms) = shipped_with(&[(cases[0].0, cases[0].1)], &roots);
+        assert_eq!(forms.unwrap_err().to_string(), format!("forms.yaml: noun_cases.acc.mold: no `accusative` mold for roots of length 1 (line {})", line(&text, "mold: accusative").unwrap()));
+    }
+
+    #[test]
+    fn line_of_follows_indentation_past_comments() {
+        let text = "a:\n  # b: 0\n  b:\n    c: 1\nb:\n\n  c: 2\n";
+        assert_eq!(line_of(text, &["a", "b", "c"]), Some(4));
+        assert_eq!(line_of(text, &["b", "c"]), Some(7));
+        assert_eq!(line_of(text, &["b"]), Some(5));
+        assert_eq!(line_of(text, &["c"]), None);
+        // keys it can't find fall back on the closest one around them
+        assert_eq!(line_of(text, &["a", "c"]), Some(1));
+        assert_eq!(line_of("a:\n  b: { c: 1 }\n", &["a", "b", "c"]), Some(2));
+    }
+
+    #[test]
+    fn unknown_and_missing_fields_are_turned_down() {
+        let roots = shipped_roots();
+        assert!(shipped_with(&[], &roots).1.is_ok());
+        let (text, forms) = shipped_with(&[("noun_plurality_suffix: \"at\"", "noun_plurality_suffix: \"at\"\ncolour: red")], &roots);
+        let error = forms.unwrap_err();
+        assert_eq!((error.file.as_str(), error.path.as_str(), error.line), ("forms.yaml", "", line(&text, "colour")));
+        assert!(error.message.starts_with("unknown field `colour`"), "{}", error);
+
+        let (text, forms) = shipped_with(&[("    perfect: ha", "    perfect: ha\n    durative: du")], &roots);
+        let error = forms.unwrap_err();
+        assert_eq!((error.path.as_str(), error.line), ("verb_particles.aspect", line(&text, "durative")));
+        assert!(error.message.starts_with("unknown field `durative`"), "{}", error);
+
+        let error = shipped_with(&[("    distal: \"non\"\n", "")], &roots).1.unwrap_err();
+        assert_eq!(error.path, "article.definite");
+        assert!(error.message.starts_with("missing field `distal`"), "{}", error);
+    }
+
+    #[test]
+    fn molds_have_to_fit_the_roots() {
+        let roots = shipped_roots();
+        let (text, forms) = shipped_with(&[("    verbal: \"-a-ar\"", "    verbal: \"-ar\" # one short")], &roots);
+        let error = forms.unwrap_err();
+        let at = line(&text, "one short").unwrap();
+        assert_eq!((error.file.as_str(), error.path.as_str(), error.line), ("forms.yaml", "root_form.2.verbal", Some(at)));
+        assert_eq!(error.to_string(), format!("forms.yaml: root_form.2.verbal: mold `-ar` has 1 slot(s) but is listed under roots of 2 consonant(s) (line {})", at));
+
+        let (text, forms) = shipped_with(&[("  \"1\":", "  \"one\":")], &roots);
+        let error = forms.unwrap_err();
+        assert_eq!((error.path.as_str(), error.line), ("root_form.one", line(&text, "\"one\"")));
+
+        // a named slot past the end of the roots that use it
+        let (text, forms) = shipped_with(&[("    adjective: \"-il\"", "    adjective: \"{C2}il\"")], &roots);
+        let error = forms.unwrap_err();
+        assert_eq!((error.path.as_str(), error.line), ("root_form.1.adjective", line(&text, "{C2}il")));
+
+        // without fallback molds long roots have nowhere to go
+        let mut roots = shipped_roots();
+        roots.insert("star".to_string(), Root::new("strlk".to_string()));
+        let (_, forms) = shipped_with(&[("  \"n\":", "  \"4\":")], &roots);
+        let error = forms.unwrap_err();
+        assert_eq!((error.path.as_str(), error.line), ("root_form", Some(1)));
+        assert!(error.message.contains("`star` has 5 consonant(s)"), "{}", error);
+    }
+
+    #[test]
+    fn roots_need_consonants() {
+        let error = roots_from_yaml("water: wd\nfire: \"\"\n").unwrap_err();
+        assert_eq!((error.file.as_str(), error.path.as_str(), error.line), ("roots.yaml", "fire", Some(2)));
+    }
+
+    #[test]
+    fn irregular_keys_need_an_inflection_a_word_can_stand_in_for() {
+        let roots = roots_from_yaml("be:\n  consonants: ps\n  irregular:\n    verbal.past: wos\n    nominative.pl: pasu\n").unwrap();
+        assert_eq!(roots["be"].irregular("verbal", Some("past")).map(|s| s.as_str()), Some("wos"));
+        assert_eq!(roots["be"].irregular("verbal", None), None);
+
+        let error = roots_from_yaml("be:\n  consonants: ps\n  irregular:\n    verbal.yesterday: wos\n").unwrap_err();
+        assert_eq!(error.path, "be.irregular.verbal.yesterday");
+        assert_eq!(error.line, Some(4));
+        assert!(error.message.contains("`yesterday`"));
+    }
+}
@@ -0,0 +1,229 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::*;
+
+/// how sure a match has to be for `Analyzer::closest` to bother with it
+pub const MIN_CONFIDENCE: f32 = 0.5;
+
+/// a word of the language close to something the player typed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    /// the word as the language writes it
+    pub word: String,
+    /// every reading of it, most likely first
+    pub analyses: Vec<Keyword>,
+    /// how far it is from what was typed, each segment added, dropped or swapped for a very different one costing 1
+    pub distance: f32,
+    /// from 0 to 1, 1 being exactly what was typed
+    pub confidence: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Place {
+    Labial,
+    /// alveolars too
+    Dental,
+    Palatal,
+    Velar,
+    Glottal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Manner {
+    Stop,
+    Fricative,
+    Nasal,
+    Liquid,
+    Glide,
+}
+
+/// what a letter sounds like, as far as telling near misses from far ones goes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Sound {
+    Consonant { place: Place, manner: Manner, voiced: bool },
+    /// height from 0 for open to 2 for close, backness from 0 for front to 2 for back
+    Vowel { height: u8, backness: u8 },
+}
+
+/// the sound of a letter as it's usually read, `None` for anything that isn't a plain latin letter
+fn sound(segment: &str) -> Option<Sound> {
+    use Manner::*;
+    use Place::*;
+    let consonant = |place, manner, voiced| Some(Sound::Consonant { place, manner, voiced });
+    let vowel = |height, backness| Some(Sound::Vowel { height, backness });
+    match segment {
+        "p" => consonant(Labial, Stop, false),
+        "b" => consonant(Labial, Stop, true),
+        "t" => consonant(Dental, Stop, false),
+        "d" => consonant(Dental, Stop, true),
+        "k" | "c" | "q" => consonant(Velar, Stop, false),
+        "g" => consonant(Velar, Stop, true),
+        "j" => consonant(Palatal, Stop, true),
+        "f" => consonant(Labial, Fricative, false),
+        "v" => consonant(Labial, Fricative, true),
+        "s" => consonant(Dental, Fricative, false),
+        "z" => consonant(Dental, Fricative, true),
+        "x" => consonant(Velar, Fricative, false),
+        "h" => consonant(Glottal, Fricative, false),
+        "m" => consonant(Labial, Nasal, true),
+        "n" => consonant(Dental, Nasal, true),
+        "l" | "r" => consonant(Dental, Liquid, true),
+        "w" => consonant(Labial, Glide, true),
+        "y" => consonant(Palatal, Glide, true),
+        "i" => vowel(2, 0),
+        "e" => vowel(1, 0),
+        "a" => vowel(0, 1),
+        "o" => vowel(1, 2),
+        "u" => vowel(2, 2),
+        _ => None,
+    }
+}
+
+/// what typing `a` for `b` costs: a voicing slip like `t` for `d` is cheap,
+/// a sound made somewhere else or some other way less so, a vowel for a consonant costs as much as anything
+fn substitution(a: &str, b: &str) -> f32 {
+    if a == b {
+        return 0.0;
+    }
+    match (sound(a), sound(b)) {
+        (Some(Sound::Consonant { place: p1, manner: m1, voiced: v1 }), Some(Sound::Consonant { place: p2, manner: m2, voiced: v2 })) => {
+            let mut cost = 0.1;
+            if v1 != v2 {cost += 0.2}
+            if p1 != p2 {cost += 0.4}
+            if m1 != m2 {cost += 0.4}
+            f32::min(cost, 1.0)
+        },
+        (Some(Sound::Vowel { height: h1, backness: b1 }), Some(Sound::Vowel { height: h2, backness: b2 })) => {
+            0.2 + 0.15 * (h1.abs_diff(h2) + b1.abs_diff(b2)) as f32
+        },
+        // `y` and `i`, `w` and `u` are nearly the same sound
+        (Some(Sound::Consonant { manner: Manner::Glide, place, .. }), Some(Sound::Vowel { height: 2, backness }))
+            | (Some(Sound::Vowel { height: 2, backness }), Some(Sound::Consonant { manner: Manner::Glide, place, .. }))
+            if (place, backness) == (Place::Palatal, 0) || (place, backness) == (Place::Labial, 2) => 0.4,
+        _ => 1.0,
+    }
+}
+
+/// what having the segment at `at` in `word` with nothing for it in the other word costs,
+/// a letter typed twice by mistake being cheap
+fn insertion(word: &[&str], at: usize) -> f32 {
+    let doubled = (at > 0 && word[at - 1] == word[at]) || word.get(at + 1) == Some(&word[at]);
+    if doubled {0.5} else {1.0}
+}
+
+/// weighted edit distance from `typed` to `word`, swapping two letters next to each other counting as one edit
+pub fn phonological_distance(typed: &str, word: &str) -> f32 {
+    let a: Vec<&str> = typed.graphemes(true).collect();
+    let b: Vec<&str> = word.graphemes(true).collect();
+
+    // distances[i][j] is from the first i segments of `a` to the first j of `b`
+    let mut distances = vec![vec![0.0; b.len() + 1]; a.len() + 1];
+    for i in 1..=a.len() {
+        distances[i][0] = distances[i - 1][0] + insertion(&a, i - 1);
+    }
+    for j in 1..=b.len() {
+        distances[0][j] = distances[0][j - 1] + insertion(&b, j - 1);
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let mut best = f32::min(
+                distances[i - 1][j - 1] + substitution(a[i - 1], b[j - 1]),
+                f32::min(distances[i - 1][j] + insertion(&a, i - 1), distances[i][j - 1] + insertion(&b, j - 1)),
+            );
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] && a[i - 1] != a[i - 2] {
+                best = f32::min(best, distances[i - 2][j - 2] + 0.8);
+            }
+            distances[i][j] = best;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+impl Analyzer {
+    /// the words of the language closest to what was typed, closest first, at most `limit` of them,
+    /// an exact match being the only one returned and nothing under `MIN_CONFIDENCE` at all
+    pub fn closest(&self, typed: &str, limit: usize) -> Vec<FuzzyMatch> {
+        let typed = typed.trim_matches(|c: char| c.is_ascii_punctuation()).to_lowercase();
+        let exact = self.analyze_word(&typed);
+        if !exact.is_empty() {
+            return vec![FuzzyMatch { word: typed, analyses: exact, distance: 0.0, confidence: 1.0 }];
+        }
+
+        let typed_length = typed.graphemes(true).count();
+        let mut matches: Vec<FuzzyMatch> = self.words()
+            .filter_map(|(word, analyses)| {
+                let length = typed_length.max(word.graphemes(true).count()).max(1) as f32;
+                // every segment one word has over the other costs at least half, which is enough to rule most words out
+                if typed_length.abs_diff(word.graphemes(true).count()) as f32 > length * (1.0 - MIN_CONFIDENCE) {
+                    return None;
+                }
+                let distance = phonological_distance(&typed, word);
+                // a word half of which is off is nothing like it
+                let confidence = f32::max(1.0 - 2.0 * distance / length, 0.0);
+                (confidence >= MIN_CONFIDENCE).then(|| FuzzyMatch { word: word.clone(), analyses: analyses.clone(), distance, confidence })
+            })
+            .collect();
+        matches.sort_by(|a, b| a.distance.total_cmp(&b.distance).then_with(|| a.word.cmp(&b.word)));
+        matches.truncate(limit);
+        matches
+    }
+
+    /// the closest word to what was typed if there's one clearly closer than the rest,
+    /// for reading commands and answers that have to resolve to something
+    pub fn resolve_word(&self, typed: &str) -> Option<FuzzyMatch> {
+        let matches = self.closest(typed, 2);
+        match &matches[..] {
+            [best, second] if best.distance == second.distance => None,
+            [best, ..] => Some(best.clone()),
+            [] => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn near_misses_cost_less_than_far_ones() {
+        let distance = phonological_distance;
+        assert_eq!(distance("kata", "kata"), 0.0);
+        // a voicing slip is cheaper than a place change, which is cheaper than a vowel for a consonant
+        assert!(distance("kada", "kata") < distance("kapa", "kata"));
+        assert!(distance("kapa", "kata") < distance("kaaa", "kata"));
+        // a letter typed twice is cheaper than a stray one, a swap is cheaper than two changes
+        assert!(distance("katta", "kata") < distance("kasta", "kata"));
+        assert!(distance("akta", "kata") < distance("ekta", "kata"));
+        // a glide for its vowel is close
+        assert!(distance("kya", "kia") < distance("kta", "kia"));
+        // letters it knows nothing about cost the most, but work
+        assert_eq!(distance("ŋa", "na"), 1.0);
+        assert_eq!(distance("kata", "kada"), distance("kada", "kata"));
+    }
+
+    #[test]
+    fn closest_ranks_words_and_drops_unlikely_ones() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let analyzer = lang.analyzer();
+        let exact = analyzer.closest("Kitu.", 5);
+        assert_eq!((exact.len(), exact[0].word.as_str(), exact[0].confidence), (1, "kitu", 1.0));
+
+        let matches = analyzer.closest("saparuni", 5);
+        assert_eq!(matches[0].word, "saparoni");
+        assert!(matches.windows(2).all(|m| m[0].distance <= m[1].distance));
+        assert!(matches.iter().all(|m| m.confidence >= MIN_CONFIDENCE));
+        assert!(analyzer.closest("zzzzzzzz", 5).is_empty());
+    }
+
+    #[test]
+    fn resolve_word_gives_up_on_ties() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let analyzer = lang.analyzer();
+        assert_eq!(analyzer.resolve_word("saparuni").map(|m| m.word), Some("saparoni".to_string()));
+        // as far from `ana` as from `ini`
+        let tied = analyzer.closest("ani", 2);
+        assert_eq!(tied.iter().map(|m| m.word.as_str()).collect::<Vec<&str>>(), vec!["ana", "ini"]);
+        assert!(analyzer.resolve_word("ani").is_none());
+        assert!(analyzer.resolve_word("zzzzzzzz").is_none());
+    }
+}
@@ -0,0 +1,509 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::ops::RangeInclusive;
+
+use super::*;
+use super::super::debug::*;
+
+/// concepts that get a root when no list is given, matching the hand written roots.yaml
+pub const DEFAULT_CONCEPTS: [&str; 12] = [
+    "hit", "be", "music", "cat", "sleep", "close", "warm", "fire", "young", "run", "water", "face",
+];
+
+/// how many parts of speech every root length gets a mold for, see `RootForms`
+const PARTS_OF_SPEECH: usize = 6;
+
+/// how many times to reroll something that came out identical to a sibling before giving up on the config
+const MAX_ATTEMPTS: usize = 200;
+
+#[derive(Debug, Clone)]
+pub struct PhonologyConfig {
+    /// consonants the inventory is drawn from
+    pub consonant_pool: Vec<char>,
+    /// vowels the inventory is drawn from
+    pub vowel_pool: Vec<char>,
+    /// how many consonants from the pool end up in the language
+    pub consonant_count: RangeInclusive<usize>,
+    /// how many vowels from the pool end up in the language, at least 3 so deixis can be told apart
+    pub vowel_count: RangeInclusive<usize>,
+    /// syllable shapes to pick from, written with `C` and `V` (e.g. "CVC")
+    pub syllable_shapes: Vec<String>,
+    /// how many consonants a root can have, every length in here gets its own set of molds
+    pub root_length: RangeInclusive<usize>,
+    /// the english keys to make roots for
+    pub concepts: Vec<String>,
+}
+
+impl Default for PhonologyConfig {
+    fn default() -> Self {
+        PhonologyConfig {
+            consonant_pool: "ptkbdgmnszfvlryhwx".chars().collect(),
+            vowel_pool: "aeiou".chars().collect(),
+            consonant_count: 8..=14,
+            vowel_count: 3..=5,
+            syllable_shapes: ["CV", "CVC", "V", "VC", "CVV"].iter().map(|s| s.to_string()).collect(),
+            root_length: 1..=3,
+            concepts: DEFAULT_CONCEPTS.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+/// splitmix64, small and stable across platforms and compiler versions, which is all a seed needs
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    fn range(&mut self, range: &RangeInclusive<usize>) -> usize {
+        range.start() + self.below(range.end() - range.start() + 1)
+    }
+
+    fn chance(&mut self, numerator: usize, denominator: usize) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    fn pick<T: Clone>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())].clone()
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+/// the sounds and shapes a generated language is built from
+struct Phonology {
+    consonants: Vec<char>,
+    vowels: Vec<char>,
+    shapes: Vec<String>,
+}
+
+impl Phonology {
+    fn new(rng: &mut Rng, config: &PhonologyConfig) -> Phonology {
+        let mut consonants = config.consonant_pool.clone();
+        let mut vowels = config.vowel_pool.clone();
+        rng.shuffle(&mut consonants);
+        rng.shuffle(&mut vowels);
+        consonants.truncate(rng.range(&config.consonant_count));
+        vowels.truncate(rng.range(&config.vowel_count));
+
+        // every language keeps at least a couple of shapes so words don't all look alike
+        let mut shapes = config.syllable_shapes.clone();
+        rng.shuffle(&mut shapes);
+        let shape_count = rng.range(&(2.min(shapes.len())..=shapes.len()));
+        shapes.truncate(shape_count);
+
+        Phonology { consonants, vowels, shapes }
+    }
+
+    fn fill(&self, rng: &mut Rng, shape: &str) -> String {
+        shape.chars().map(|c| match c {
+            'C' => rng.pick(&self.consonants),
+            'V' => rng.pick(&self.vowels),
+            other => other,
+        }).collect()
+    }
+
+    fn syllable(&self, rng: &mut Rng) -> String {
+        let shape = rng.pick(&self.shapes);
+        self.fill(rng, &shape)
+    }
+
+    /// something that can be glued onto the end of a word, always starting with a vowel
+    fn suffix(&self, rng: &mut Rng) -> String {
+        let shape = rng.pick(&["V", "VC", "VC", "VCV"]);
+        self.fill(rng, shape)
+    }
+}
+
+/// calls `make` until it gives something not already in `taken`, so sibling forms stay distinguishable,
+/// errors if it never does, which means the inventory is too small for all the `what` it has to tell apart
+fn distinct<F>(rng: &mut Rng, taken: &mut Vec<String>, what: &str, mut make: F) -> Result<String, String>
+where F: FnMut(&mut Rng) -> String {
+    for _ in 0..MAX_ATTEMPTS {
+        let candidate = make(rng);
+        if !taken.contains(&candidate) {
+            taken.push(candidate.clone());
+            return Ok(candidate);
+        }
+    }
+    Err(format!("Ran out of distinct {} after {} ({} tries), the phonology is too small for them", what, taken.len(), MAX_ATTEMPTS))
+}
+
+fn generate_roots(rng: &mut Rng, phonology: &Phonology, config: &PhonologyConfig) -> Result<Roots, String> {
+    let mut taken = vec![];
+    let mut roots = Roots::new();
+    for concept in &config.concepts {
+        let root = distinct(rng, &mut taken, "roots", |rng| {
+            let length = rng.range(&config.root_length);
+            (0..length).map(|_| rng.pick(&phonology.consonants)).collect()
+        })?;
+        roots.insert(concept.clone(), Root::new(root));
+    }
+    Ok(roots)
+}
+
+/// one mold per part of speech for every root length, e.g. "-a-ar" for a two consonant verbal
+///
+/// each part of speech keeps the same vowels between slots and the same ending across lengths,
+/// so the part of speech stays recognisable no matter how long the root is
+fn generate_root_forms(rng: &mut Rng, phonology: &Phonology, config: &PhonologyConfig) -> Result<BTreeMap<String, RootForms>, String> {
+    let longest = *config.root_length.end();
+    // endings are kept distinct so even single consonant roots tell the parts of speech apart
+    let mut taken_endings = vec![];
+    let mut patterns = vec![];
+    for _ in 0..PARTS_OF_SPEECH {
+        let mut pieces = vec![];
+        for _ in 1..longest {
+            pieces.push(match rng.below(4) {
+                0 => phonology.fill(rng, "VV"),
+                1 => phonology.fill(rng, "VC"),
+                _ => phonology.fill(rng, "V"),
+            });
+        }
+        pieces.push(distinct(rng, &mut taken_endings, "mold endings", |rng| phonology.suffix(rng))?);
+        patterns.push(pieces);
+    }
+
+    let mut root_form = BTreeMap::new();
+    for length in config.root_length.clone() {
+        let mold = |pieces: &Vec<String>| {
+            let mut mold = String::from("-");
+            for filler in &pieces[..length - 1] {
+                mold.push_str(filler);
+                mold.push('-');
+            }
+            mold.push_str(&pieces[pieces.len() - 1]);
+            mold
+        };
+        root_form.insert(length.to_string(), RootForms {
+            adjective: mold(&patterns[0]),
+            nominative: mold(&patterns[1]),
+            verbal: mold(&patterns[2]),
+            verbal_adjective: mold(&patterns[3]),
+            prepositional: mold(&patterns[4]),
+            adject_adjective: mold(&patterns[5]),
+            other: BTreeMap::new(),
+        });
+    }
+    Ok(root_form)
+}
+
+/// a set of deictic words sharing one frame, told apart by vowel
+///
+/// the immediate form is the proximal form with its vowel echoed, like "nin" and "nini"
+fn generate_deictic_set(rng: &mut Rng, phonology: &Phonology, deixis_vowels: &[char; 3], taken: &mut Vec<String>) -> Result<ArticleSet, String> {
+    // consonants are filled in now, vowels are left as `*` until we know the deixis
+    let frame = distinct(rng, taken, "deictic words", |rng| {
+        let shape = rng.pick(&["CVC", "CV", "VC"]).replace('V', "*");
+        phonology.fill(rng, &shape)
+    })?;
+    let with_vowel = |v: char| -> String {frame.replace('*', &v.to_string())};
+
+    let proximal = with_vowel(deixis_vowels[0]);
+    Ok(ArticleSet {
+        non_spatial: with_vowel(deixis_vowels[2]),
+        immediate: format!("{}{}", proximal, deixis_vowels[0]),
+        proximal,
+        distal: with_vowel(deixis_vowels[1]),
+    })
+}
+
+fn generate_forms(rng: &mut Rng, phonology: &Phonology, config: &PhonologyConfig) -> Result<Forms, String> {
+    let root_form = generate_root_forms(rng, phonology, config)?;
+
+    // proximal, distal and non spatial each get their own vowel, used for every deictic word
+    let mut deixis_vowels = phonology.vowels.clone();
+    rng.shuffle(&mut deixis_vowels);
+    let deixis_vowels = [deixis_vowels[0], deixis_vowels[1], deixis_vowels[2]];
+
+    let mut taken_frames = vec![];
+    let article = Articles {
+        definite: generate_deictic_set(rng, phonology, &deixis_vowels, &mut taken_frames)?,
+        indefinite: generate_deictic_set(rng, phonology, &deixis_vowels, &mut taken_frames)?,
+    };
+    let mut deictic_noun_set = |rng: &mut Rng| -> Result<DeicticNounSet, String> {
+        let set = generate_deictic_set(rng, phonology, &deixis_vowels, &mut taken_frames)?;
+        Ok(DeicticNounSet { proximal: set.proximal, immediate: set.immediate, distal: set.distal })
+    };
+    let deictic_nouns = DeicticNouns {
+        spatial: deictic_noun_set(rng)?,
+        temporal: deictic_noun_set(rng)?,
+    };
+
+    let mut taken_endings = vec![];
+    let mut ending = |rng: &mut Rng| distinct(rng, &mut taken_endings, "endings", |rng| phonology.suffix(rng));
+    let verb_tense_endings = TenseEndings {
+        future: ending(rng)?,
+        present: ending(rng)?,
+        past: ending(rng)?,
+    };
+    let verb_infinitive_suffix = if rng.chance(1, 2) {ending(rng)?} else {String::new()};
+    let verb_imperative_suffix = ending(rng)?;
+    let noun_plurality_suffix = ending(rng)?;
+    let noun_dual_suffix = if rng.chance(1, 3) {Some(ending(rng)?)} else {None};
+    let noun_paucal_suffix = if rng.chance(1, 5) {Some(ending(rng)?)} else {None};
+    // the cases most languages with case at all tell apart, nominative stays unmarked
+    let noun_cases = [Case::Accusative, Case::Genitive, Case::Dative, Case::Locative].iter()
+        .map(|case| Ok((case.as_str().to_string(), CaseForm { suffix: ending(rng)?, mold: None })))
+        .collect::<Result<_, String>>()?;
+    let adjective_case_agreement = rng.chance(1, 2);
+    // derivations are all suffixes, so they need no molds of their own
+    let derivations = [
+        ("agent", "AGT", vec!["nom"], vec!["verb"]),
+        ("abst", "ABST", vec!["nom"], vec!["adj"]),
+        ("dim", "DIM", vec!["nom", "adj"], vec![]),
+        ("caus", "CAUS", vec!["verb"], vec!["verb"]),
+    ];
+    let derivations = derivations.into_iter()
+        .map(|(name, gloss, keywords, from)| Ok((name.to_string(), Derivation {
+            mold: None,
+            suffix: ending(rng)?,
+            gloss: gloss.to_string(),
+            keywords: keywords.into_iter().map(String::from).collect(),
+            from: from.into_iter().map(String::from).collect(),
+        })))
+        .collect::<Result<_, String>>()?;
+    let mut verb_agreement = BTreeMap::new();
+    for person in &Person::ALL {
+        for number in &Number::ALL {
+            verb_agreement.insert(person_key(person, number, None, None), ending(rng)?);
+        }
+    }
+
+    let mut taken_particles = vec![];
+    let mut particle = |rng: &mut Rng| distinct(rng, &mut taken_particles, "particles", |rng| phonology.syllable(rng));
+    let verb_particles = VerbParticles {
+        aspect: AspectParticles {
+            completive: particle(rng)?,
+            progressive: particle(rng)?,
+            habitual: particle(rng)?,
+            perfect: particle(rng)?,
+        },
+    };
+    // verb final languages mostly have postpositions, the others mostly prepositions
+    let clause = rng.pick(&["sov", "svo", "vso"]).to_string();
+    let word_order = WordOrder {
+        adpositions: Some(if clause == "sov" {Placement::After} else {Placement::Before}),
+        adjectives: Some(if rng.chance(1, 2) {Placement::Before} else {Placement::After}),
+        articles: Some(Placement::Before),
+        numerals: Some(Placement::Before),
+        clause: Some(clause),
+    };
+    // compounds mostly put their modifiers where adjectives go
+    let compounding = Some(Compounding {
+        mold: None,
+        head: if word_order.adjectives == Some(Placement::Before) {Placement::After} else {Placement::Before},
+        link: String::new(),
+    });
+
+    let negation = Some(Marker { form: particle(rng)?, placement: MarkerPlacement::Before });
+    let question = Some(Marker {
+        form: particle(rng)?,
+        placement: if rng.chance(1, 2) {MarkerPlacement::After} else {MarkerPlacement::Before},
+    });
+    let question_words = ["who", "what", "where", "when", "why", "how"].iter()
+        .map(|word| Ok((word.to_string(), particle(rng)?)))
+        .collect::<Result<_, String>>()?;
+
+    let mut pronouns = BTreeMap::new();
+    for person in &Person::ALL {
+        for number in &Number::ALL {
+            pronouns.insert(person_key(person, number, None, None), particle(rng)?);
+        }
+    }
+
+    // up to 23 numeral words would run a small inventory out of single syllables, so they can be two long,
+    // still kept apart from every particle
+    let base = rng.pick(&[NumeralBase::Decimal, NumeralBase::Decimal, NumeralBase::Duodecimal, NumeralBase::Vigesimal]);
+    let mut taken_numerals = taken_particles.clone();
+    let mut numeral = |rng: &mut Rng| distinct(rng, &mut taken_numerals, "numeral words", |rng| {
+        let syllables = if rng.chance(1, 2) {1} else {2};
+        (0..syllables).map(|_| phonology.syllable(rng)).collect()
+    });
+    let numerals = Some(Numerals {
+        base,
+        digits: (0..base.radix()).map(|_| numeral(rng)).collect::<Result<_, String>>()?,
+        powers: (0..3).map(|_| numeral(rng)).collect::<Result<_, String>>()?,
+        multiplier: Placement::Before,
+        omit_one: rng.chance(1, 2),
+        order: if rng.chance(1, 4) {NumeralOrder::Ascending} else {NumeralOrder::Descending},
+        link: String::new(),
+    });
+
+    Ok(Forms {
+        root_form,
+        article,
+        deictic_nouns,
+        verb_tense_endings,
+        verb_infinitive_suffix,
+        verb_imperative_suffix,
+        negation,
+        question,
+        question_words,
+        noun_plurality_suffix,
+        noun_dual_suffix,
+        noun_paucal_suffix,
+        noun_cases,
+        adjective_case_agreement,
+        verb_particles,
+        verb_agreement,
+        pronouns,
+        phonology: PhonologicalRules::default(),
+        numerals,
+        derivations,
+        compounding,
+        keywords: KeywordDefinitions::new(),
+        grammar: GrammarRules::default(),
+        word_order,
+    })
+}
+
+impl PhonologyConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.consonant_pool.is_empty() || self.vowel_pool.is_empty() {
+            return Err("Consonant and vowel pools must not be empty".to_string());
+        }
+        if *self.consonant_count.start() == 0 || *self.consonant_count.end() > self.consonant_pool.len() {
+            return Err(format!("Consonant count {:?} doesn't fit a pool of {}", self.consonant_count, self.consonant_pool.len()));
+        }
+        if *self.vowel_count.start() < 3 || *self.vowel_count.end() > self.vowel_pool.len() {
+            return Err(format!("Vowel count {:?} must be at least 3 and fit a pool of {}", self.vowel_count, self.vowel_pool.len()));
+        }
+        if self.syllable_shapes.is_empty() || self.syllable_shapes.iter().any(|s| s.is_empty() || s.chars().any(|c| c != 'C' && c != 'V')) {
+            return Err("Syllable shapes must be non-empty and made only of `C` and `V`".to_string());
+        }
+        if *self.root_length.start() == 0 || self.root_length.is_empty() {
+            return Err(format!("Root length {:?} must be a non-empty range starting at 1 or more", self.root_length));
+        }
+        Ok(())
+    }
+}
+
+impl Lang {
+    /// builds a whole language from a seed, the same seed and config always give the same language
+    ///
+    /// errors if the config is invalid, or if its inventory can't make enough distinct words for everything
+    pub fn generate(seed: u64, config: PhonologyConfig) -> Result<Lang, Box<dyn Error>> {
+        config.validate().ctx("Invalid phonology config")?;
+
+        let mut rng = Rng(seed);
+        let phonology = Phonology::new(&mut rng, &config);
+        let roots = generate_roots(&mut rng, &phonology, &config).ctx(format!("Failed to generate roots from seed {}", seed))?;
+        let forms = generate_forms(&mut rng, &phonology, &config).ctx(format!("Failed to generate forms from seed {}", seed))?;
+
+        let lang = Lang {
+            forms,
+            roots,
+            rules: RuleSet::default(),
+        };
+        // checked the same way a loaded language is, so whatever `save` writes loads again
+        let (forms_str, roots_str) = lang.to_yaml()?;
+        Ok(Lang::from_yaml(&forms_str, &roots_str).ctx(format!("Generated an invalid language from seed {}", seed))?)
+    }
+
+    /// forms.yaml and roots.yaml as `save` writes them
+    fn to_yaml(&self) -> Result<(String, String), String> {
+        let forms_str = serde_yaml::to_string(&self.forms).ctx("Failed to serialize forms")?;
+        let roots_str = serde_yaml::to_string(&self.roots).ctx("Failed to serialize roots")?;
+        Ok((forms_str, roots_str))
+    }
+
+    /// writes the language out as forms.yaml and roots.yaml, loadable again with `Lang::load`
+    pub fn save(&self, static_path: &str) -> Result<(), Box<dyn Error>> {
+        let (forms_str, roots_str) = self.to_yaml()?;
+
+        fs::create_dir_all(static_path).ctx(format!("Failed to create {}", static_path))?;
+        fs::write(format!("{}/forms.yaml", static_path), forms_str).ctx(format!("Failed to write forms.yaml to {}", static_path))?;
+        fs::write(format!("{}/roots.yaml", static_path), roots_str).ctx(format!("Failed to write roots.yaml to {}", static_path))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(lang: &Lang) -> (String, String) {
+        (serde_yaml::to_string(&lang.forms).unwrap(), serde_yaml::to_string(&lang.roots).unwrap())
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_language() {
+        for seed in [0, 1, 42, u64::MAX] {
+            let a = Lang::generate(seed, PhonologyConfig::default()).unwrap();
+            let b = Lang::generate(seed, PhonologyConfig::default()).unwrap();
+            assert_eq!(yaml(&a), yaml(&b), "seed {}", seed);
+        }
+        let a = Lang::generate(1, PhonologyConfig::default()).unwrap();
+        let b = Lang::generate(2, PhonologyConfig::default()).unwrap();
+        assert_ne!(yaml(&a), yaml(&b));
+    }
+
+    #[test]
+    fn sibling_forms_are_distinct() {
+        for seed in 0..20 {
+            let lang = Lang::generate(seed, PhonologyConfig::default()).unwrap();
+            let mut roots: Vec<&String> = lang.roots.values().map(|r| &r.consonants).collect();
+            let count = roots.len();
+            roots.sort();
+            roots.dedup();
+            assert_eq!(roots.len(), count, "seed {}", seed);
+
+            let numerals = lang.forms.numerals.as_ref().unwrap();
+            let mut words: Vec<&String> = numerals.digits.iter().chain(&numerals.powers).collect();
+            let count = words.len();
+            words.sort();
+            words.dedup();
+            assert_eq!(words.len(), count, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn saved_languages_load_again() {
+        let path = std::env::temp_dir().join(format!("lingot-generate-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        for seed in [0, 7, 1234] {
+            let lang = Lang::generate(seed, PhonologyConfig::default()).unwrap();
+            lang.save(path).unwrap();
+            let loaded = Lang::load(path).unwrap();
+            assert_eq!(yaml(&loaded), yaml(&lang), "seed {}", seed);
+            // and it can say something
+            let root = lang.roots.keys().next().unwrap();
+            assert_eq!(loaded.render(&format!("nom{{{} 0}}", root)).unwrap(), lang.render(&format!("nom{{{} 0}}", root)).unwrap());
+        }
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn too_small_an_inventory_is_an_error() {
+        let config = PhonologyConfig {
+            consonant_pool: vec!['p', 't', 'k'],
+            vowel_pool: vec!['a', 'i', 'u'],
+            consonant_count: 3..=3,
+            vowel_count: 3..=3,
+            syllable_shapes: vec!["CV".to_string()],
+            // three one consonant roots for a dozen concepts
+            root_length: 1..=1,
+            ..PhonologyConfig::default()
+        };
+        for seed in 0..5 {
+            assert!(Lang::generate(seed, config.clone()).is_err(), "seed {}", seed);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::fmt;
+
+use super::*;
+
+/// one column of an interlinear gloss
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlossWord {
+    /// the word as it's rendered, e.g. "yinaat"
+    pub surface: String,
+    /// the word split at morpheme boundaries, e.g. "yina-at"
+    pub segmented: String,
+    /// one label per morpheme, e.g. "young-PL"
+    pub gloss: String,
+    /// whether any of it comes from an irregular form in roots.yaml instead of the molds
+    pub irregular: bool,
+}
+
+/// a leipzig style interlinear gloss, displays as three lines with the words lined up in columns,
+/// followed by a note for every irregular word
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gloss {
+    pub words: Vec<GlossWord>,
+}
+
+impl GlossWord {
+    /// `surface` is the word after the phonological rules ran, the other lines show the morphemes before
+    pub fn new(surface: String, morphemes: &[Morpheme]) -> GlossWord {
+        GlossWord {
+            surface,
+            segmented: morphemes.iter().map(|m| m.form.as_str()).collect::<Vec<&str>>().join("-"),
+            gloss: morphemes.iter().map(|m| m.gloss.as_str()).collect::<Vec<&str>>().join("-"),
+            irregular: morphemes.iter().any(|m| m.irregular),
+        }
+    }
+}
+
+impl fmt::Display for Gloss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: [fn(&GlossWord) -> &String; 3] = [|w| &w.surface, |w| &w.segmented, |w| &w.gloss];
+        for (i, line) in lines.iter().enumerate() {
+            if i > 0 {writeln!(f)?}
+            let mut out = String::new();
+            for word in &self.words {
+                let width = word.surface.chars().count()
+                    .max(word.segmented.chars().count())
+                    .max(word.gloss.chars().count());
+                out.push_str(&format!("{:<width$} ", line(word), width = width));
+            }
+            write!(f, "{}", out.trim_end())?;
+        }
+        for (i, word) in self.words.iter().enumerate().filter(|(_, w)| w.irregular) {
+            write!(f, "\n  word {}: {} is irregular", i + 1, word.segmented)?;
+        }
+        Ok(())
+    }
+}
+
+impl Lang {
+    /// renders markup as an interlinear gloss, errors are already formatted for showing to whoever wrote the markup
+    pub fn gloss(&self, text: &str) -> Result<Gloss, String> {
+        let keywords = self.parse(text)
+            .map_err(|errors| errors.iter().map(|e| e.to_string()).collect::<Vec<String>>().join("\n"))?;
+        let morphemes = self.sentence_morphemes(&keywords)?;
+        let (surfaces, _) = self.apply_rules(&morphemes);
+        let words = surfaces.into_iter()
+            .zip(&morphemes)
+            .map(|(surface, m)| GlossWord::new(surface, m))
+            .collect();
+        Ok(Gloss { words })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nouns_are_glossed_by_root_and_case_alone() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let gloss = lang.gloss("artDef{nspac} nom{cat 0} aspProg verb{sleep pres} artDef{nspac} nom{fire 0 loc}").unwrap();
+        let labels: Vec<&str> = gloss.words.iter().map(|w| w.gloss.as_str()).collect();
+        assert!(labels.contains(&"cat"), "{:?}", labels);
+        assert!(labels.contains(&"fire-LOC"), "{:?}", labels);
+        assert!(labels.contains(&"sleep.V-PRS.3SG"), "{:?}", labels);
+        assert!(!labels.iter().any(|l| l.contains("NOM")), "{:?}", labels);
+
+        let gloss = lang.gloss("nom{young 1}").unwrap();
+        assert_eq!(gloss.words[0].gloss, "young-PL");
+    }
+
+    #[test]
+    fn adjectives_take_the_case_of_the_noun_before_them() {
+        let mut lang = Lang::load("assets/lang").unwrap();
+        let labels = |lang: &Lang| lang.gloss("nom{fire 0 loc} adj{warm} adj{young}").unwrap().words.into_iter().map(|w| w.gloss).collect::<Vec<String>>();
+        lang.forms.adjective_case_agreement = true;
+        assert_eq!(labels(&lang), vec!["fire-LOC", "warm.ADJ-LOC", "young.ADJ-LOC"]);
+        lang.forms.adjective_case_agreement = false;
+        assert_eq!(labels(&lang), vec!["fire-LOC", "warm.ADJ", "young.ADJ"]);
+    }
+
+    #[test]
+    fn irregular_words_are_one_morpheme_and_flagged() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let gloss = lang.gloss("pron{3 sg} verb{be past}").unwrap();
+        let wos = &gloss.words[1];
+        assert_eq!((wos.segmented.as_str(), wos.gloss.as_str(), wos.irregular), ("wos", "be.V.PST.3SG", true));
+        assert!(gloss.to_string().ends_with("word 2: wos is irregular"));
+
+        // the same root with a regular ending isn't
+        let gloss = lang.gloss("pron{3 sg} verb{be pres}").unwrap();
+        assert_eq!((gloss.words[1].gloss.as_str(), gloss.words[1].irregular), ("be.V-PRS.3SG", false));
+        assert!(!gloss.to_string().contains("irregular"));
+
+        // endings the irregular form doesn't stand in for still go on after it
+        let gloss = lang.gloss("nom{cat 1 loc}").unwrap();
+        assert_eq!((gloss.words[0].segmented.as_str(), gloss.words[0].gloss.as_str()), ("kitu-ne", "cat.PL-LOC"));
+    }
+}
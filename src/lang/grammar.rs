@@ -0,0 +1,262 @@
+use std::fmt;
+use std::ops::Range;
+
+use super::*;
+
+/// the categories grammar rules in forms.yaml can refer to, besides the names of declared keywords
+pub const CATEGORIES: [&str; 9] = ["adjective", "noun", "pronoun", "verb", "preposition", "aspect", "article", "question", "numeral"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhraseKind {
+    /// articles, numerals and adjectives around a noun or pronoun
+    Noun,
+    /// aspect particles and the verb they go with
+    Verb,
+    /// a preposition and the noun phrase after it
+    Prepositional,
+    /// anything that doesn't go with its neighbours, like a declared keyword
+    Other,
+}
+
+/// a run of keywords that belong together
+#[derive(Debug, Clone, PartialEq)]
+pub struct Phrase {
+    pub kind: PhraseKind,
+    /// which keywords of the sentence it covers
+    pub keywords: Range<usize>,
+    /// the keyword the phrase is built around, if it has one, e.g. the noun of a noun phrase
+    pub head: Option<usize>,
+}
+
+/// something the grammar checker thinks is wrong with a sentence, which still renders all the same
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrammarWarning {
+    pub span: Span,
+    pub message: String,
+    pub hint: String,
+}
+
+impl fmt::Display for GrammarWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {} (hint: {})",
+            self.span.line, self.span.column, self.message, self.hint
+        )
+    }
+}
+
+impl Keyword {
+    /// the category grammar rules know this keyword by, see `CATEGORIES`
+    pub fn category(&self) -> &str {
+        match self {
+            Keyword::Adjective(_) | Keyword::VerbalAdjective(_) | Keyword::AdjectAdjective(_) => "adjective",
+            Keyword::Nominative(_, _, _) | Keyword::DeicticSpatialNoun(_) | Keyword::DeicticTemporalNoun(_) => "noun",
+            Keyword::Pronoun(_, _, _, _) => "pronoun",
+            Keyword::Verbal(_, _, _, _, _) => "verb",
+            Keyword::Interrogative(_) => "question",
+            Keyword::Numeral(_) => "numeral",
+            Keyword::Prepositional(_) => "preposition",
+            Keyword::CompletiveAspect | Keyword::ProgressiveAspect | Keyword::HabitualAspect | Keyword::PerfectAspect => "aspect",
+            Keyword::DefiniteArticle(_) | Keyword::IndefiniteArticle(_) => "article",
+            Keyword::Defined(name, _, _) => name,
+        }
+    }
+
+    /// the person and number of a subject, for nouns and pronouns in the nominative
+    pub fn as_subject(&self) -> Option<(Person, Number)> {
+        match self {
+            Keyword::Pronoun(person, number, _, _) => Some((person.clone(), number.clone())),
+            Keyword::Nominative(_, number, Case::Nominative) => Some((Person::Third, number.agreement())),
+            _ => None,
+        }
+    }
+}
+
+/// splits a sentence into phrases, every keyword ends up in exactly one
+pub fn group_phrases(keywords: &[Keyword]) -> Vec<Phrase> {
+    let is = |i: usize, categories: &[&str]| keywords.get(i).is_some_and(|k| categories.contains(&k.category()));
+
+    // articles, numerals and adjectives, then maybe a noun or pronoun and more adjectives after it
+    let noun_phrase = |start: usize| -> (usize, Option<usize>) {
+        let mut i = start;
+        while is(i, &["article", "numeral", "adjective"]) {i += 1}
+        if !is(i, &["noun", "pronoun"]) {return (i, None)}
+        let head = i;
+        i += 1;
+        while is(i, &["adjective"]) {i += 1}
+        (i, Some(head))
+    };
+
+    let mut phrases = vec![];
+    let mut i = 0;
+    while i < keywords.len() {
+        let (kind, end, head) = match keywords[i].category() {
+            "article" | "numeral" | "adjective" | "noun" | "pronoun" => {
+                let (end, head) = noun_phrase(i);
+                (PhraseKind::Noun, end, head)
+            },
+            "aspect" | "verb" => {
+                let mut end = i;
+                while is(end, &["aspect"]) {end += 1}
+                let head = is(end, &["verb"]).then_some(end);
+                (PhraseKind::Verb, end + head.map_or(0, |_| 1), head)
+            },
+            "preposition" => {
+                let (end, _) = noun_phrase(i + 1);
+                (PhraseKind::Prepositional, end, Some(i))
+            },
+            _ => (PhraseKind::Other, i + 1, None),
+        };
+        phrases.push(Phrase { kind, keywords: i..end, head });
+        i = end;
+    }
+    phrases
+}
+
+/// a span running from the start of the first keyword to the end of the last
+fn cover(sentence: &[(Keyword, Span)]) -> Option<Span> {
+    let ((_, first), (_, last)) = (sentence.first()?, sentence.last()?);
+    Some(Span { end: last.end, ..*first })
+}
+
+impl Lang {
+    /// holds a sentence to the rules under `grammar` in forms.yaml, as `Lang::parse_spans` gives it,
+    /// the spans are only used to say where the problems are
+    pub fn check(&self, sentence: &[(Keyword, Span)]) -> Vec<GrammarWarning> {
+        let rules = &self.forms.grammar;
+        let keywords: Vec<Keyword> = sentence.iter().map(|(k, _)| k.clone()).collect();
+        let span = |i: usize| sentence[i].1;
+        let mut warnings = vec![];
+        let describe = |categories: &[String]| categories.iter().map(|c| format!("a {}", c)).collect::<Vec<String>>().join(" or ");
+
+        let phrases = group_phrases(&keywords);
+        for phrase in &phrases {
+            for i in phrase.keywords.clone() {
+                let category = keywords[i].category();
+                let after = |categories: &Vec<String>| (i + 1..phrase.keywords.end).any(|j| categories.iter().any(|c| c == keywords[j].category()));
+                let before = |categories: &Vec<String>| (phrase.keywords.start..i).any(|j| categories.iter().any(|c| c == keywords[j].category()));
+                if let Some(categories) = rules.must_precede.get(category).filter(|c| !after(c)) {
+                    warnings.push(GrammarWarning {
+                        span: span(i),
+                        message: format!("this {} has no {} after it", category, categories.join(" or ")),
+                        hint: format!("follow it with {}", describe(categories)),
+                    });
+                }
+                if let Some(categories) = rules.must_follow.get(category).filter(|c| !before(c)) {
+                    warnings.push(GrammarWarning {
+                        span: span(i),
+                        message: format!("this {} has no {} before it", category, categories.join(" or ")),
+                        hint: format!("put {} before it", describe(categories)),
+                    });
+                }
+            }
+        }
+
+        for category in &rules.required {
+            if !keywords.iter().any(|k| k.category() == category) && let Some(span) = cover(sentence) {
+                warnings.push(GrammarWarning {
+                    span,
+                    message: format!("the sentence has no {}", category),
+                    hint: format!("add a {}", category),
+                });
+            }
+        }
+
+        if rules.subject_verb_agreement {
+            // the subject is the head of the last noun phrase before the verb, prepositional phrases don't count
+            let mut subject: Option<usize> = None;
+            for phrase in &phrases {
+                match (phrase.kind, phrase.head) {
+                    (PhraseKind::Noun, Some(head)) if keywords[head].as_subject().is_some() => subject = Some(head),
+                    (PhraseKind::Verb, Some(head)) => {
+                        let Keyword::Verbal(_, _, Some((person, number)), _, _) = &keywords[head] else {continue};
+                        let Some(s) = subject else {continue};
+                        let Some((subject_person, subject_number)) = keywords[s].as_subject() else {continue};
+                        if (person, number) != (&subject_person, &subject_number) {
+                            warnings.push(GrammarWarning {
+                                span: span(head),
+                                message: format!(
+                                    "the verb is {} but its subject is {}",
+                                    person_key(person, number, None, None),
+                                    person_key(&subject_person, &subject_number, None, None),
+                                ),
+                                hint: format!(
+                                    "make it `{} {}` or leave the person and number out so it agrees on its own",
+                                    subject_person.as_str(), subject_number.as_str(),
+                                ),
+                            });
+                        }
+                    },
+                    _ => (),
+                }
+            }
+        }
+
+        warnings.sort_by_key(|w| w.span.start);
+        warnings
+    }
+
+    /// parses markup and runs `Lang::check` over it, for checking written content when it's loaded
+    pub fn check_markup(&self, text: &str) -> Result<Vec<GrammarWarning>, Vec<MarkupError>> {
+        Ok(self.check(&self.parse_spans(text)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phrases(text: &str) -> Vec<(PhraseKind, Range<usize>, Option<usize>)> {
+        group_phrases(&to_object(text).unwrap()).into_iter().map(|p| (p.kind, p.keywords, p.head)).collect()
+    }
+
+    #[test]
+    fn groups_keywords_into_phrases() {
+        assert_eq!(
+            phrases("artDef{nspac} num{3} nom{cat 1} adj{warm} aspProg verb{sleep pres} prep{face} artDef{dist} nom{water 0}"),
+            vec![
+                (PhraseKind::Noun, 0..4, Some(2)),
+                (PhraseKind::Verb, 4..6, Some(5)),
+                (PhraseKind::Prepositional, 6..9, Some(6)),
+            ],
+        );
+        // an article with no noun still makes a phrase of its own, and so does an aspect without its verb
+        assert_eq!(
+            phrases("artDef{nspac} aspProg pron{3 sg}"),
+            vec![(PhraseKind::Noun, 0..1, None), (PhraseKind::Verb, 1..2, None), (PhraseKind::Noun, 2..3, Some(2))],
+        );
+    }
+
+    #[test]
+    fn warns_about_missing_neighbours() {
+        let mut lang = Lang::load("assets/lang").unwrap();
+        assert_eq!(lang.check_markup("artDef{nspac} nom{cat 0} verb{sleep pres}").unwrap(), vec![]);
+
+        let text = "artDef{nspac} aspProg verb{sleep pres}";
+        let warnings = lang.check_markup(text).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(&text[warnings[0].span.start..warnings[0].span.end], "artDef{nspac}");
+        assert!(warnings[0].message.contains("no noun after it"));
+
+        lang.forms.grammar.must_follow.insert("adjective".to_string(), vec!["noun".to_string()]);
+        let text = "adj{warm} nom{cat 0} adj{warm} verb{sleep pres}";
+        let warnings = lang.check_markup(text).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!((warnings[0].span.start, warnings[0].span.column), (0, 1));
+        assert!(warnings[0].message.contains("no noun before it"));
+    }
+
+    #[test]
+    fn warns_about_missing_verbs_and_disagreement() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let warnings = lang.check_markup("artDef{nspac} nom{cat 0}").unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("no verb"));
+
+        let text = "nom{cat 1} verb{sleep pres 1 sg}";
+        let warnings = lang.check_markup(text).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(&text[warnings[0].span.start..warnings[0].span.end], "verb{sleep pres 1 sg}");
+    }
+}
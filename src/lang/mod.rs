@@ -1,5 +1,38 @@
+pub mod analyze;
+pub mod challenge;
+pub mod derive;
+pub mod dictionary;
+pub mod english;
+pub mod error;
+pub mod forms;
+pub mod fuzzy;
+pub mod generate;
+pub mod gloss;
+pub mod grammar;
+pub mod mold;
+pub mod numeral;
+pub mod phonology;
+pub mod reference;
 pub mod render;
 pub mod to_object;
+pub mod translate;
+pub mod variables;
+pub mod vocabulary;
+pub mod word_order;
 
+pub use analyze::*;
+pub use challenge::*;
+pub use derive::*;
+pub use error::*;
+pub use forms::*;
+pub use fuzzy::*;
+pub use generate::*;
+pub use gloss::*;
+pub use grammar::*;
+pub use mold::*;
+pub use phonology::*;
+pub use reference::*;
 pub use render::*;
-pub use to_object::*;
\ No newline at end of file
+pub use to_object::*;
+pub use variables::*;
+pub use vocabulary::*;
@@ -0,0 +1,148 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// one piece of a mold like `{C1}a{C2}{C2}i-`
+#[derive(Debug, Clone, PartialEq)]
+enum Piece<'a> {
+    /// copied as is
+    Literal(&'a str),
+    /// `-`, the next root consonant not yet taken by another `-`
+    Next,
+    /// `{C1}`, `{C2}`, ... or counted from the end with `{C-1}`
+    Consonant(isize),
+    /// `{V1}`, `{V2}`, ..., a vowel from the root's melody
+    Vowel(usize),
+    /// `{C*}` or `{C*:a}`, every consonant no other slot uses, joined by whatever follows the `:`
+    Rest(&'a str),
+}
+
+/// a slot along with any combining marks written on it, which are moved onto whatever fills it
+type Slot<'a> = (Piece<'a>, &'a str);
+
+/// a mold split into slots and literals
+pub struct Mold<'a> {
+    pieces: Vec<Slot<'a>>,
+}
+
+/// splits a root like `ktb` or `ktb/ai` into its consonants and its vowel melody, by grapheme
+pub fn split_root(root: &str) -> (Vec<&str>, Vec<&str>) {
+    let (consonants, vowels) = root.split_once('/').unwrap_or((root, ""));
+    (consonants.graphemes(true).collect(), vowels.graphemes(true).collect())
+}
+
+impl<'a> Mold<'a> {
+    pub fn parse(mold: &'a str) -> Result<Mold<'a>, String> {
+        let mut pieces = vec![];
+        let mut rest = mold;
+        while !rest.is_empty() {
+            let grapheme = rest.graphemes(true).next().unwrap_or(rest);
+            if let Some(marks) = grapheme.strip_prefix('-') {
+                pieces.push((Piece::Next, marks));
+                rest = &rest[grapheme.len()..];
+            } else if grapheme.starts_with('{') {
+                let close = rest.find('}').ok_or(format!("Slot in `{}` is never closed with `}}`", mold))?;
+                let name = &rest[1..close];
+                let after = &rest[close..];
+                // marks after the `}` belong to the slot, the same as marks on a `-`
+                let closing = after.graphemes(true).next().unwrap_or(after);
+                pieces.push((Mold::parse_slot(name, mold)?, &closing[1..]));
+                rest = &after[closing.len()..];
+            } else if grapheme.starts_with('}') {
+                return Err(format!("Unmatched `}}` in `{}`", mold));
+            } else {
+                pieces.push((Piece::Literal(grapheme), ""));
+                rest = &rest[grapheme.len()..];
+            }
+        }
+        Ok(Mold { pieces })
+    }
+
+    fn parse_slot(name: &'a str, mold: &str) -> Result<Piece<'a>, String> {
+        let invalid = || format!("Unknown slot `{{{}}}` in `{}`, expected something like {{C1}}, {{C-1}}, {{V1}} or {{C*}}", name, mold);
+        if let Some(separator) = name.strip_prefix("C*") {
+            return match separator.strip_prefix(':') {
+                Some(separator) => Ok(Piece::Rest(separator)),
+                None if separator.is_empty() => Ok(Piece::Rest("")),
+                None => Err(invalid()),
+            };
+        }
+        if let Some(index) = name.strip_prefix('C') {
+            return match index.parse::<isize>() {
+                Ok(i) if i != 0 => Ok(Piece::Consonant(i)),
+                _ => Err(invalid()),
+            };
+        }
+        if let Some(index) = name.strip_prefix('V') {
+            return match index.parse::<usize>() {
+                Ok(i) if i != 0 => Ok(Piece::Vowel(i)),
+                _ => Err(invalid()),
+            };
+        }
+        Err(invalid())
+    }
+
+    /// how many `-` slots there are
+    pub fn anonymous_slots(&self) -> usize {
+        self.pieces.iter().filter(|(p, _)| *p == Piece::Next).count()
+    }
+
+    /// whether the mold only uses `-`, in which case it has to use every consonant exactly once
+    pub fn is_anonymous(&self) -> bool {
+        self.pieces.iter().all(|(p, _)| matches!(p, Piece::Literal(_) | Piece::Next))
+    }
+
+    /// fills the slots with the consonants and vowels of `root`
+    pub fn fill(&self, root: &str) -> Result<String, String> {
+        let (consonants, vowels) = split_root(root);
+
+        let consonant = |i: isize| -> Result<usize, String> {
+            let index = if i > 0 {i - 1} else {consonants.len() as isize + i};
+            if index < 0 || index as usize >= consonants.len() {
+                return Err(format!("Slot {{C{}}} is out of range for a root of {} consonant(s)", i, consonants.len()));
+            }
+            Ok(index as usize)
+        };
+
+        // everything a `-` or a named slot uses is off limits for {C*}
+        let mut used = vec![false; consonants.len()];
+        let mut next = 0;
+        for (piece, _) in &self.pieces {
+            match piece {
+                Piece::Next => {
+                    if next >= consonants.len() {
+                        return Err("Pattern has more indicators for consonants (denoted by `-`) than the root has consonants!".to_string());
+                    }
+                    used[next] = true;
+                    next += 1;
+                },
+                Piece::Consonant(i) => used[consonant(*i)?] = true,
+                _ => (),
+            }
+        }
+
+        let mut out = String::new();
+        let mut next = consonants.iter();
+        for (piece, marks) in &self.pieces {
+            match piece {
+                Piece::Literal(text) => out.push_str(text),
+                Piece::Next => {
+                    // checked above, there's always one left
+                    if let Some(c) = next.next() {out.push_str(c)}
+                },
+                Piece::Consonant(i) => out.push_str(consonants[consonant(*i)?]),
+                Piece::Vowel(i) => out.push_str(vowels.get(i - 1).ok_or(
+                    format!("Slot {{V{}}} needs a root with at least {} vowel(s), write them after a `/` like `ktb/ai`", i, i)
+                )?),
+                Piece::Rest(separator) => {
+                    let rest: Vec<&str> = consonants.iter().zip(&used).filter(|(_, u)| !**u).map(|(c, _)| *c).collect();
+                    out.push_str(&rest.join(separator));
+                },
+            }
+            out.push_str(marks);
+        }
+
+        if self.is_anonymous() && next.next().is_some() {
+            return Err("Pattern is missing one or more indicators for consonant patters (denoted by `-`)!".to_string());
+        }
+        Ok(out)
+    }
+}
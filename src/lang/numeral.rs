@@ -0,0 +1,166 @@
+use super::*;
+
+impl NumeralBase {
+    pub fn radix(&self) -> u64 {
+        match self {
+            NumeralBase::Decimal => 10,
+            NumeralBase::Duodecimal => 12,
+            NumeralBase::Vigesimal => 20,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            NumeralBase::Decimal => "decimal",
+            NumeralBase::Duodecimal => "duodecimal",
+            NumeralBase::Vigesimal => "vigesimal",
+        }
+    }
+}
+
+impl Numerals {
+    /// the biggest number there are words for, one short of the power after the last one
+    pub fn max(&self) -> u64 {
+        let radix = self.base.radix();
+        u32::try_from(self.powers.len() + 1).ok()
+            .and_then(|p| radix.checked_pow(p))
+            .map_or(u64::MAX, |n| n - 1)
+    }
+
+    /// the digits of `number` paired with the power of the base they count, zeros left out
+    /// and in the order they're said, e.g. 37 in decimal is `[(3, 1), (7, 0)]`
+    pub fn terms(&self, number: u64) -> Vec<(u64, usize)> {
+        let radix = self.base.radix();
+        let mut terms = vec![];
+        let (mut rest, mut power) = (number, 0);
+        while rest > 0 {
+            if rest % radix != 0 {
+                terms.push((rest % radix, power));
+            }
+            rest /= radix;
+            power += 1;
+        }
+        if self.order == NumeralOrder::Descending {
+            terms.reverse();
+        }
+        terms
+    }
+
+    /// the whole number as one word, glossed digit by digit like `3-10-LNK-7`
+    pub fn morphemes(&self, number: u64) -> Result<Vec<Morpheme>, String> {
+        if number > self.max() {
+            return Err(format!("{} is too big for the numerals in forms.yaml", number));
+        }
+        let radix = self.base.radix();
+        let digit = |d: u64| Morpheme::new(&self.digits[d as usize], d.to_string());
+        if number == 0 {
+            return Ok(vec![digit(0)]);
+        }
+
+        let mut morphemes = vec![];
+        for (i, (d, power)) in self.terms(number).into_iter().enumerate() {
+            if i > 0 && !self.link.is_empty() {
+                morphemes.push(Morpheme::new(&self.link, "LNK"));
+            }
+            if power == 0 {
+                morphemes.push(digit(d));
+                continue;
+            }
+            let power_word = Morpheme::new(&self.powers[power - 1], radix.pow(power as u32).to_string());
+            let multiplier = (d != 1 || !self.omit_one).then(|| digit(d));
+            match self.multiplier {
+                Placement::Before => morphemes.extend(multiplier.into_iter().chain([power_word])),
+                Placement::After => morphemes.extend([power_word].into_iter().chain(multiplier)),
+            }
+        }
+        Ok(morphemes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal() -> Numerals {
+        Numerals {
+            base: NumeralBase::Decimal,
+            digits: ["nul", "un", "du", "tri", "kar", "pen", "ses", "sep", "ok", "nau"].map(String::from).to_vec(),
+            powers: ["dek", "sen", "mil"].map(String::from).to_vec(),
+            multiplier: Placement::Before,
+            omit_one: false,
+            order: NumeralOrder::Descending,
+            link: String::new(),
+        }
+    }
+
+    fn words(numerals: &Numerals, number: u64) -> String {
+        numerals.morphemes(number).unwrap().into_iter().map(|m| m.form).collect::<Vec<String>>().join("-")
+    }
+
+    #[test]
+    fn terms_leave_out_zeros_in_either_order() {
+        let mut numerals = decimal();
+        assert_eq!(numerals.terms(307), vec![(3, 2), (7, 0)]);
+        assert_eq!(numerals.terms(0), vec![]);
+        numerals.order = NumeralOrder::Ascending;
+        assert_eq!(numerals.terms(307), vec![(7, 0), (3, 2)]);
+        assert_eq!(words(&numerals, 37), "sep-tri-dek");
+    }
+
+    #[test]
+    fn zero_is_its_own_digit() {
+        assert_eq!(words(&decimal(), 0), "nul");
+    }
+
+    #[test]
+    fn omit_one_drops_the_multiplier_of_one() {
+        let mut numerals = decimal();
+        assert_eq!(words(&numerals, 110), "un-sen-un-dek");
+        numerals.omit_one = true;
+        assert_eq!(words(&numerals, 110), "sen-dek");
+        // a lone one is still said
+        assert_eq!(words(&numerals, 1), "un");
+    }
+
+    #[test]
+    fn links_and_multipliers_after_show_in_the_gloss() {
+        let mut numerals = decimal();
+        numerals.link = "e".to_string();
+        numerals.multiplier = Placement::After;
+        let glosses: Vec<String> = numerals.morphemes(37).unwrap().into_iter().map(|m| m.gloss).collect();
+        assert_eq!(glosses, vec!["10", "3", "LNK", "7"]);
+    }
+
+    #[test]
+    fn max_is_one_short_of_the_next_power() {
+        let mut numerals = decimal();
+        assert_eq!(numerals.max(), 9999);
+        assert!(numerals.morphemes(9999).is_ok());
+        assert!(numerals.morphemes(10000).is_err());
+
+        numerals.base = NumeralBase::Vigesimal;
+        numerals.digits = (0..20).map(|d| d.to_string()).collect();
+        assert_eq!(numerals.max(), 20u64.pow(4) - 1);
+
+        // more powers than a u64 can count just means no limit
+        numerals.powers = (0..30).map(|p| format!("p{}", p)).collect();
+        assert_eq!(numerals.max(), u64::MAX);
+    }
+
+    #[test]
+    fn dual_and_paucal_need_a_suffix_to_parse() {
+        assert_eq!(to_object("nom{water du} nom{water pc}").unwrap(), vec![
+            Keyword::Nominative("water".to_string(), NounNumber::Dual, Case::Nominative),
+            Keyword::Nominative("water".to_string(), NounNumber::Paucal, Case::Nominative),
+        ]);
+
+        let mut lang = Lang::load("assets/lang").unwrap();
+        assert!(lang.parse("nom{water du} nom{water pc}").is_ok());
+        lang.forms.noun_dual_suffix = None;
+        lang.forms.noun_paucal_suffix = None;
+        let errors = lang.parse("nom{water du} nom{water pc}").unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|e| e.kind == MarkupErrorKind::InvalidParameter));
+        assert!(errors[0].message.contains("dual") && errors[1].message.contains("paucal"));
+    }
+}
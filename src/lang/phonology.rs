@@ -0,0 +1,383 @@
+use std::collections::BTreeMap;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::*;
+
+/// a word or sentence as the rules see it, segments with the boundaries between them kept
+#[derive(Debug, Clone, PartialEq)]
+enum Unit {
+    Segment(String),
+    /// `+`, between two morphemes of the same word
+    Morpheme,
+    /// `#`, between two words
+    Word,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Element {
+    Literal(String),
+    /// `{V}`, any member of a class from `phonology.classes`
+    Class(String),
+    Morpheme,
+    /// matches between words as well as at the very start or end
+    Word,
+}
+
+#[derive(Debug, Clone)]
+enum RuleKind {
+    /// `focus -> replacement / left _ right`
+    Rewrite {
+        focus: Vec<Element>,
+        replacement: Vec<Element>,
+        left: Vec<Element>,
+        right: Vec<Element>,
+    },
+    /// `harmony`, suffix vowels take the set of the last harmonic vowel before them
+    Harmony,
+}
+
+/// one compiled line of `phonology.rules`
+#[derive(Debug, Clone)]
+pub struct Rule {
+    /// the rule as it was written, for traces
+    pub source: String,
+    kind: RuleKind,
+}
+
+/// a rule changing a word while rendering
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleFiring {
+    pub rule: String,
+    /// which word of the sentence it fired on, from 0
+    pub word: usize,
+    pub before: String,
+    pub after: String,
+}
+
+/// the compiled rules along with the classes they refer to
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+    classes: BTreeMap<String, Vec<String>>,
+    harmony: BTreeMap<String, Vec<String>>,
+}
+
+fn parse_elements(text: &str, classes: &BTreeMap<String, Vec<String>>) -> Result<Vec<Element>, String> {
+    let mut elements = vec![];
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        let grapheme = rest.graphemes(true).next().unwrap_or(rest);
+        let mut length = grapheme.len();
+        match grapheme {
+            " " | "\t" => (),
+            "+" => elements.push(Element::Morpheme),
+            "#" => elements.push(Element::Word),
+            // both mean "nothing", for deletions like `e -> 0 / _+a`
+            "0" | "∅" => (),
+            "{" => {
+                let close = rest.find('}').ok_or(format!("class in `{}` is never closed with `}}`", text))?;
+                let name = &rest[1..close];
+                if !classes.contains_key(name) {
+                    return Err(format!("no class `{}` in phonology.classes", name));
+                }
+                elements.push(Element::Class(name.to_string()));
+                length = close + 1;
+            },
+            _ => elements.push(Element::Literal(grapheme.to_string())),
+        }
+        rest = &rest[length..];
+    }
+    Ok(elements)
+}
+
+impl Rule {
+    pub fn parse(source: &str, classes: &BTreeMap<String, Vec<String>>) -> Result<Rule, String> {
+        if source.trim() == "harmony" {
+            return Ok(Rule { source: source.to_string(), kind: RuleKind::Harmony });
+        }
+
+        let (focus, rest) = source.split_once("->").ok_or(format!("rule `{}` has no `->`", source))?;
+        let (replacement, environment) = rest.split_once('/').unwrap_or((rest, "_"));
+        let (left, right) = environment.split_once('_').ok_or(format!("environment of `{}` has no `_`", source))?;
+
+        let focus = parse_elements(focus, classes)?;
+        let replacement = parse_elements(replacement, classes)?;
+        if focus.is_empty() {
+            return Err(format!("rule `{}` has nothing to the left of `->`", source));
+        }
+        // word boundaries can only be context, rules change words but never join or split them
+        if focus.iter().chain(&replacement).any(|e| matches!(e, Element::Word)) {
+            return Err(format!("rule `{}` can only use `#` after the `/`", source));
+        }
+        // classes on the right of `->` map onto the classes on the left by position
+        let count = |elements: &[Element]| elements.iter().filter(|e| matches!(e, Element::Class(_))).count();
+        if count(&replacement) > count(&focus) {
+            return Err(format!("rule `{}` has more classes after `->` than before it", source));
+        }
+
+        Ok(Rule {
+            source: source.to_string(),
+            kind: RuleKind::Rewrite {
+                focus,
+                replacement,
+                left: parse_elements(left, classes)?,
+                right: parse_elements(right, classes)?,
+            },
+        })
+    }
+}
+
+impl RuleSet {
+    pub fn new(phonology: &PhonologicalRules) -> Result<RuleSet, (usize, String)> {
+        let mut rules = vec![];
+        for (i, source) in phonology.rules.iter().enumerate() {
+            rules.push(Rule::parse(source, &phonology.classes).map_err(|e| (i, e))?);
+        }
+        Ok(RuleSet {
+            rules,
+            classes: phonology.classes.clone(),
+            harmony: phonology.harmony.clone(),
+        })
+    }
+
+    /// index of `segment` in `class`, if it's a member
+    fn member(&self, class: &str, segment: &str) -> Option<usize> {
+        self.classes.get(class)?.iter().position(|s| s == segment)
+    }
+
+    /// tries to match `elements` starting at `start`, walking forwards or backwards,
+    /// and hands back where it stopped and the class member indices it picked up
+    fn match_at(&self, units: &[Unit], start: usize, elements: &[Element], forwards: bool, skip_first: bool) -> Option<(usize, Vec<usize>)> {
+        let mut position = start;
+        let mut captures = vec![];
+        let step = |p: usize| if forwards {Some(p + 1)} else {p.checked_sub(1)};
+        let at = |p: usize| -> Option<&Unit> {
+            if forwards {units.get(p)} else {p.checked_sub(1).and_then(|p| units.get(p))}
+        };
+
+        let ordered: Vec<&Element> = if forwards {elements.iter().collect()} else {elements.iter().rev().collect()};
+        for (i, element) in ordered.into_iter().enumerate() {
+            // morpheme boundaries only matter to rules that mention them
+            if (i > 0 || skip_first) && *element != Element::Morpheme {
+                while at(position) == Some(&Unit::Morpheme) {
+                    position = step(position)?;
+                }
+            }
+            match (element, at(position)) {
+                (Element::Word, None) => continue,
+                (Element::Word, Some(Unit::Word)) => (),
+                (Element::Morpheme, Some(Unit::Morpheme)) => (),
+                (Element::Literal(l), Some(Unit::Segment(s))) if l == s => (),
+                (Element::Class(c), Some(Unit::Segment(s))) => captures.push(self.member(c, s)?),
+                _ => return None,
+            }
+            position = step(position)?;
+        }
+
+        if !forwards {captures.reverse()}
+        Some((position, captures))
+    }
+
+    fn rewrite(&self, units: &mut Vec<Unit>, rule: &Rule, firings: &mut Vec<RuleFiring>) {
+        let RuleKind::Rewrite { focus, replacement, left, right } = &rule.kind else {return};
+        let mut position = 0;
+        while position < units.len() {
+            let matched = self.match_at(units, position, focus, true, false)
+                .filter(|_| self.match_at(units, position, left, false, true).is_some())
+                .and_then(|(end, captures)| self.match_at(units, end, right, true, true).map(|_| (end, captures)));
+
+            let (end, captures) = match matched {
+                Some(m) => m,
+                None => {position += 1; continue},
+            };
+
+            let mut captures = captures.into_iter();
+            let new: Vec<Unit> = replacement.iter().filter_map(|e| match e {
+                Element::Literal(l) => Some(Unit::Segment(l.clone())),
+                Element::Morpheme => Some(Unit::Morpheme),
+                Element::Class(c) => {
+                    let index = captures.next()?;
+                    self.classes[c].get(index).map(|s| Unit::Segment(s.clone()))
+                },
+                Element::Word => None,
+            }).collect();
+
+            let word = units[..position].iter().filter(|u| **u == Unit::Word).count();
+            let before = word_text(units, word);
+            let length = new.len();
+            units.splice(position..end, new);
+            let after = word_text(units, word);
+            if before != after {
+                firings.push(RuleFiring { rule: rule.source.clone(), word, before, after });
+            }
+
+            // move past what was written so a rule never rewrites its own output,
+            // a deletion stays put since everything after it has moved up
+            position += length;
+        }
+    }
+
+    fn harmonize(&self, units: &mut [Unit], rule: &Rule, firings: &mut Vec<RuleFiring>) {
+        let set_of = |segment: &str| -> Option<(&String, usize)> {
+            self.harmony.iter().find_map(|(name, set)| set.iter().position(|s| s == segment).map(|i| (name, i)))
+        };
+
+        let mut word = 0;
+        let mut trigger: Option<&String> = None;
+        let mut in_suffix = false;
+        let mut before = word_text(units, 0);
+        for i in 0..units.len() {
+            match units[i].clone() {
+                Unit::Word => {
+                    push_if_changed(units, rule, word, &mut before, firings);
+                    word += 1; trigger = None; in_suffix = false;
+                    before = word_text(units, word);
+                },
+                Unit::Morpheme => in_suffix = true,
+                Unit::Segment(s) => {
+                    if let Some((set, index)) = set_of(&s) {
+                        match trigger {
+                            Some(t) if in_suffix && t != set => {
+                                if let Some(replacement) = self.harmony[t].get(index) {
+                                    units[i] = Unit::Segment(replacement.clone());
+                                }
+                            },
+                            _ => trigger = Some(set),
+                        }
+                    }
+                },
+            }
+        }
+        push_if_changed(units, rule, word, &mut before, firings);
+    }
+
+    /// runs every rule in order over `units`, recording what each one changed
+    fn apply(&self, units: &mut Vec<Unit>) -> Vec<RuleFiring> {
+        let mut firings = vec![];
+        for rule in &self.rules {
+            match &rule.kind {
+                RuleKind::Rewrite { .. } => self.rewrite(units, rule, &mut firings),
+                RuleKind::Harmony => self.harmonize(units, rule, &mut firings),
+            }
+        }
+        firings
+    }
+}
+
+fn push_if_changed(units: &[Unit], rule: &Rule, word: usize, before: &mut String, firings: &mut Vec<RuleFiring>) {
+    let after = word_text(units, word);
+    if *before != after {
+        firings.push(RuleFiring { rule: rule.source.clone(), word, before: before.clone(), after });
+    }
+}
+
+/// the segments of word `index`, without boundaries
+fn word_text(units: &[Unit], index: usize) -> String {
+    units.split(|u| *u == Unit::Word)
+        .nth(index)
+        .map(|word| word.iter().filter_map(|u| match u {Unit::Segment(s) => Some(s.as_str()), _ => None}).collect())
+        .unwrap_or_default()
+}
+
+impl Lang {
+    /// joins the morphemes of each word, applying the phonological rules at every boundary,
+    /// rules see the whole sentence so they can work across words too
+    pub fn apply_rules(&self, words: &[Vec<Morpheme>]) -> (Vec<String>, Vec<RuleFiring>) {
+        let mut units = vec![];
+        for (i, morphemes) in words.iter().enumerate() {
+            if i > 0 {units.push(Unit::Word)}
+            for (j, morpheme) in morphemes.iter().enumerate() {
+                if j > 0 {units.push(Unit::Morpheme)}
+                units.extend(morpheme.form.graphemes(true).map(|g| Unit::Segment(g.to_string())));
+            }
+        }
+
+        let firings = self.rules.apply(&mut units);
+        let words = (0..words.len()).map(|i| word_text(&units, i)).collect();
+        (words, firings)
+    }
+
+    /// renders markup like `Lang::render`, along with every rule that changed a word on the way
+    pub fn render_with_trace(&self, text: &str) -> Result<(String, Vec<RuleFiring>), Vec<MarkupError>> {
+        let keywords = self.parse_spans(text)?;
+        let morphemes = self.sentence_morphemes(&keywords.iter().map(|(k, _)| k.clone()).collect::<Vec<Keyword>>())
+            .map_err(|e| self.unrenderable(text, &keywords, e))?;
+        let (words, firings) = self.apply_rules(&morphemes);
+        Ok((words.iter().map(|w| format!("{} ", w)).collect(), firings))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `words` joined and run through `rules`, with `a e i o u` as `V` and front `e i` against back `a u` for harmony
+    fn apply(rules: &[&str], words: &[&[&str]]) -> (Vec<String>, Vec<RuleFiring>) {
+        let mut lang = Lang::load("assets/lang").unwrap();
+        let phonology = PhonologicalRules {
+            classes: BTreeMap::from([("V".to_string(), ["a", "e", "i", "o", "u"].map(String::from).to_vec())]),
+            rules: rules.iter().map(|r| r.to_string()).collect(),
+            harmony: BTreeMap::from([
+                ("front".to_string(), vec!["e".to_string(), "i".to_string()]),
+                ("back".to_string(), vec!["a".to_string(), "u".to_string()]),
+            ]),
+        };
+        lang.rules = RuleSet::new(&phonology).unwrap();
+        let words: Vec<Vec<Morpheme>> = words.iter().map(|w| w.iter().map(|m| Morpheme::new(*m, "X")).collect()).collect();
+        lang.apply_rules(&words)
+    }
+
+    fn surface(rules: &[&str], words: &[&[&str]]) -> Vec<String> {
+        apply(rules, words).0
+    }
+
+    #[test]
+    fn merges_across_morpheme_boundaries() {
+        assert_eq!(surface(&["a+a -> a"], &[&["kita", "at"], &["kaat"]]), vec!["kitat", "kaat"]);
+        assert_eq!(surface(&["{V}+{V} -> {V}"], &[&["yina", "at"]]), vec!["yinat"]);
+    }
+
+    #[test]
+    fn rewrites_in_context() {
+        assert_eq!(surface(&["n -> m / _p"], &[&["anpa"], &["anta"]]), vec!["ampa", "anta"]);
+        // a boundary in between doesn't stop a rule that doesn't mention it
+        assert_eq!(surface(&["n -> m / _p"], &[&["an", "pa"]]), vec!["ampa"]);
+    }
+
+    #[test]
+    fn deletes_with_zero() {
+        assert_eq!(surface(&["e -> 0 / _+a"], &[&["kide", "ar"], &["kide"]]), vec!["kidar", "kide"]);
+        assert_eq!(surface(&["e -> ∅ / _#"], &[&["kide"], &["ek"]]), vec!["kid", "ek"]);
+    }
+
+    #[test]
+    fn word_boundaries_match_at_the_edges_and_between_words() {
+        assert_eq!(surface(&["k -> g / #_"], &[&["kak"], &["kuk"]]), vec!["gak", "guk"]);
+        assert_eq!(surface(&["k -> x / _#"], &[&["kak"], &["kuk"]]), vec!["kax", "kux"]);
+        // `#` only goes after the `/`
+        assert!(Rule::parse("k# -> x", &BTreeMap::new()).is_err());
+    }
+
+    #[test]
+    fn harmony_spreads_into_suffixes_only() {
+        assert_eq!(surface(&["harmony"], &[&["kit", "ar", "u"], &["kat", "ir"], &["kiat"]]), vec!["kiteri", "katur", "kiat"]);
+    }
+
+    #[test]
+    fn rules_run_in_order_and_are_traced() {
+        let (words, firings) = apply(&["a+a -> a", "n -> m / _p"], &[&["kana", "an", "pa"]]);
+        assert_eq!(words, vec!["kanampa"]);
+        let traced: Vec<(&str, &str, &str)> = firings.iter().map(|f| (f.rule.as_str(), f.before.as_str(), f.after.as_str())).collect();
+        assert_eq!(traced, vec![("a+a -> a", "kanaanpa", "kananpa"), ("n -> m / _p", "kananpa", "kanampa")]);
+    }
+
+    #[test]
+    fn bad_rules_are_rejected() {
+        let classes = BTreeMap::new();
+        assert!(Rule::parse("a a", &classes).is_err());
+        assert!(Rule::parse("-> a", &classes).is_err());
+        assert!(Rule::parse("a -> b / c", &classes).is_err());
+        assert!(Rule::parse("{V} -> a", &classes).is_err());
+    }
+}
@@ -0,0 +1,349 @@
+use super::*;
+use super::dictionary::normalize;
+
+/// a piece of the language reference, written out as either markdown or html
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    /// a level from 1 and its text
+    Heading(usize, String),
+    Paragraph(String),
+    List(Vec<String>),
+    /// a header row and the rows under it
+    Table(Vec<String>, Vec<Vec<String>>),
+}
+
+/// stands in for forms that fail to render, so one bad mold doesn't sink the whole reference
+const MISSING: &str = "-";
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+pub fn blocks_to_markdown(blocks: &[Block]) -> String {
+    let mut out = String::new();
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => out.push_str(&format!("{} {}\n", "#".repeat(*level), text)),
+            Block::Paragraph(text) => out.push_str(&format!("{}\n", text)),
+            Block::List(items) => {
+                for item in items {
+                    out.push_str(&format!("- {}\n", item));
+                }
+            },
+            Block::Table(header, rows) => {
+                let row = |cells: &Vec<String>| format!("| {} |\n", cells.iter().map(|c| escape_markdown(c)).collect::<Vec<String>>().join(" | "));
+                out.push_str(&row(header));
+                out.push_str(&format!("|{}\n", " --- |".repeat(header.len())));
+                for cells in rows {
+                    out.push_str(&row(cells));
+                }
+            },
+        }
+        out.push('\n');
+    }
+    out
+}
+
+pub fn blocks_to_html(blocks: &[Block]) -> String {
+    let mut out = String::from("<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Language reference</title></head>\n<body>\n");
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => out.push_str(&format!("<h{0}>{1}</h{0}>\n", level, escape_html(text))),
+            Block::Paragraph(text) => out.push_str(&format!("<p>{}</p>\n", escape_html(text))),
+            Block::List(items) => {
+                out.push_str("<ul>\n");
+                for item in items {
+                    out.push_str(&format!("<li>{}</li>\n", escape_html(item)));
+                }
+                out.push_str("</ul>\n");
+            },
+            Block::Table(header, rows) => {
+                let row = |cells: &Vec<String>, tag: &str| {
+                    let cells: String = cells.iter().map(|c| format!("<{0}>{1}</{0}>", tag, escape_html(c))).collect();
+                    format!("<tr>{}</tr>\n", cells)
+                };
+                out.push_str("<table>\n");
+                out.push_str(&row(header, "th"));
+                for cells in rows {
+                    out.push_str(&row(cells, "td"));
+                }
+                out.push_str("</table>\n");
+            },
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+impl Lang {
+    /// the reference as markdown, see `Lang::reference`
+    pub fn reference_markdown(&self) -> String {
+        blocks_to_markdown(&self.reference())
+    }
+
+    /// the reference as a standalone html page, see `Lang::reference`
+    pub fn reference_html(&self) -> String {
+        blocks_to_html(&self.reference())
+    }
+
+    /// everything a writer needs to write in the language, generated from forms.yaml and roots.yaml
+    /// so it can't drift from them: the closed classes of words, every form of every root, and the lexicon both ways
+    pub fn reference(&self) -> Vec<Block> {
+        let mut blocks = vec![Block::Heading(1, "Language reference".to_string())];
+        blocks.extend(self.grammar_reference());
+        blocks.extend(self.root_reference());
+        blocks.extend(self.lexicon_reference());
+        blocks
+    }
+
+    fn show(&self, keyword: Keyword) -> String {
+        keyword.render(self).unwrap_or_else(|_| MISSING.to_string())
+    }
+
+    fn grammar_reference(&self) -> Vec<Block> {
+        let forms = &self.forms;
+        let mut blocks = vec![Block::Heading(2, "Grammar".to_string())];
+
+        let deixes = [Deixis::NonSpatial, Deixis::Proximal, Deixis::Immediate, Deixis::Distal];
+        blocks.push(Block::Heading(3, "Articles".to_string()));
+        blocks.push(Block::Table(
+            vec!["deixis".to_string(), "definite".to_string(), "indefinite".to_string()],
+            deixes.iter().map(|d| vec![
+                d.as_str().replace('_', " "),
+                forms.article.definite.get(d).to_string(),
+                forms.article.indefinite.get(d).to_string(),
+            ]).collect(),
+        ));
+
+        blocks.push(Block::Heading(3, "Deictic nouns".to_string()));
+        blocks.push(Block::Table(
+            vec!["deixis".to_string(), "spatial".to_string(), "temporal".to_string()],
+            deixes[1..].iter().map(|d| vec![
+                d.as_str().to_string(),
+                forms.deictic_nouns.spatial.get(d).unwrap_or(MISSING).to_string(),
+                forms.deictic_nouns.temporal.get(d).unwrap_or(MISSING).to_string(),
+            ]).collect(),
+        ));
+
+        let aspect = &forms.verb_particles.aspect;
+        blocks.push(Block::Heading(3, "Aspect particles".to_string()));
+        blocks.push(Block::List(vec![
+            format!("completive: {}", aspect.completive),
+            format!("progressive: {}", aspect.progressive),
+            format!("habitual: {}", aspect.habitual),
+            format!("perfect: {}", aspect.perfect),
+        ]));
+
+        let ending = |e: &str| if e.is_empty() {MISSING.to_string()} else {format!("-{}", e)};
+        let mut verb_endings = vec![
+            vec!["present".to_string(), ending(&forms.verb_tense_endings.present)],
+            vec!["future".to_string(), ending(&forms.verb_tense_endings.future)],
+            vec!["past".to_string(), ending(&forms.verb_tense_endings.past)],
+            vec!["infinitive".to_string(), ending(&forms.verb_infinitive_suffix)],
+            vec!["imperative".to_string(), ending(&forms.verb_imperative_suffix)],
+        ];
+        verb_endings.extend(forms.verb_agreement.iter().map(|(key, e)| vec![format!("subject {}", key), ending(e)]));
+        blocks.push(Block::Heading(3, "Verb endings".to_string()));
+        blocks.push(Block::Table(vec!["form".to_string(), "ending".to_string()], verb_endings));
+
+        let mut noun_endings = vec![vec!["plural".to_string(), ending(&forms.noun_plurality_suffix)]];
+        for (name, suffix) in [("dual", &forms.noun_dual_suffix), ("paucal", &forms.noun_paucal_suffix)] {
+            if let Some(suffix) = suffix {
+                noun_endings.push(vec![name.to_string(), ending(suffix)]);
+            }
+        }
+        noun_endings.extend(forms.noun_cases.iter().map(|(case, form)| vec![format!("case {}", case), ending(&form.suffix)]));
+        blocks.push(Block::Heading(3, "Noun endings".to_string()));
+        blocks.push(Block::Table(vec!["form".to_string(), "ending".to_string()], noun_endings));
+
+        if !forms.pronouns.is_empty() {
+            blocks.push(Block::Heading(3, "Pronouns".to_string()));
+            blocks.push(Block::Table(
+                vec!["person".to_string(), "pronoun".to_string()],
+                forms.pronouns.iter().map(|(key, form)| vec![key.clone(), form.clone()]).collect(),
+            ));
+        }
+
+        if !forms.question_words.is_empty() {
+            blocks.push(Block::Heading(3, "Question words".to_string()));
+            blocks.push(Block::List(forms.question_words.iter().map(|(word, form)| format!("{}: {}", word, form)).collect()));
+        }
+
+        if let Some(numerals) = &forms.numerals {
+            blocks.push(Block::Heading(3, "Numbers".to_string()));
+            blocks.push(Block::Paragraph(format!("Counting is {}, up to {}.", numerals.base.as_str(), numerals.max())));
+            let radix = numerals.base.radix();
+            let shown = (0..=radix).chain((1..=numerals.powers.len() as u32).map_while(|p| radix.checked_pow(p)).skip(1));
+            blocks.push(Block::Table(
+                vec!["number".to_string(), "word".to_string()],
+                shown.map(|n| vec![n.to_string(), self.show(Keyword::Numeral(n))]).collect(),
+            ));
+        }
+
+        if !forms.derivations.is_empty() {
+            blocks.push(Block::Heading(3, "Derivations".to_string()));
+            blocks.push(Block::Table(
+                vec!["name".to_string(), "gloss".to_string(), "makes".to_string(), "from".to_string()],
+                forms.derivations.iter().map(|(name, d)| vec![
+                    name.clone(),
+                    d.gloss.clone(),
+                    if d.keywords.is_empty() {"anything".to_string()} else {d.keywords.join(", ")},
+                    if d.from.is_empty() {"anything".to_string()} else {d.from.join(", ")},
+                ]).collect(),
+            ));
+        }
+        blocks
+    }
+
+    /// every mold filled with every root, then a conjugation and declension table where the root allows it
+    fn root_reference(&self) -> Vec<Block> {
+        let mut blocks = vec![Block::Heading(2, "Roots".to_string())];
+        for (key, root) in &self.roots {
+            blocks.push(Block::Heading(3, format!("{} ({})", key, root.consonants)));
+            let mut about = vec![format!("means: {}", root.english(key).join(", "))];
+            if !root.keywords.is_empty() {
+                about.push(format!("used as: {}", root.keywords.join(", ")));
+            }
+            if let Some(field) = &root.field {
+                about.push(format!("field: {}", field));
+            }
+            if let Some(notes) = &root.notes {
+                about.push(format!("notes: {}", notes));
+            }
+            for example in &root.examples {
+                about.push(format!("example: {} = {}", example, self.render(example).map_or(MISSING.to_string(), |s| s.trim().to_string())));
+            }
+            blocks.push(Block::List(about));
+
+            if let Some((_, molds)) = self.forms.molds_for(consonant_count(&root.consonants)) {
+                blocks.push(Block::Table(
+                    vec!["mold".to_string(), "form".to_string()],
+                    molds.iter().map(|(part, _)| vec![
+                        part.replace('_', " "),
+                        self.stem(part, key, "")
+                            .map(|m| m.iter().map(|m| m.form.as_str()).collect())
+                            .unwrap_or_else(|_| MISSING.to_string()),
+                    ]).collect(),
+                ));
+            }
+
+            if root.allows("verb") {
+                let subjects: Vec<Option<(Person, Number)>> = match self.forms.verb_agreement.is_empty() {
+                    true => vec![None],
+                    false => Person::ALL.iter()
+                        .flat_map(|p| Number::ALL.iter().map(move |n| Some((p.clone(), n.clone()))))
+                        .collect(),
+                };
+                let header = std::iter::once("verb".to_string())
+                    .chain(subjects.iter().map(|s| s.as_ref().map_or("form".to_string(), |(p, n)| person_key(p, n, None, None))))
+                    .collect();
+                let rows = VerbForm::ALL.iter().map(|form| {
+                    std::iter::once(form.as_str().to_string())
+                        .chain(subjects.iter().map(|subject| {
+                            // infinitives and imperatives don't agree
+                            let subject = subject.clone().filter(|_| matches!(form, VerbForm::TensePresent | VerbForm::TenseFuture | VerbForm::TensePast));
+                            self.show(Keyword::Verbal(key.clone(), form.clone(), subject, Polarity::Positive, Mood::Statement))
+                        }))
+                        .collect()
+                }).collect();
+                blocks.push(Block::Table(header, rows));
+            }
+
+            if root.allows("nom") {
+                let mut numbers = vec![NounNumber::Singular];
+                if self.forms.noun_dual_suffix.is_some() {numbers.push(NounNumber::Dual)}
+                if self.forms.noun_paucal_suffix.is_some() {numbers.push(NounNumber::Paucal)}
+                numbers.push(NounNumber::Plural);
+                let cases = Case::ALL.into_iter().filter(|c| *c == Case::Nominative || self.forms.noun_cases.contains_key(c.as_str()));
+                let header = std::iter::once("case".to_string()).chain(numbers.iter().map(|n| n.as_name().to_string())).collect();
+                let rows = cases.map(|case| {
+                    std::iter::once(case.as_str().to_string())
+                        .chain(numbers.iter().map(|number| self.show(Keyword::Nominative(key.clone(), number.clone(), case.clone()))))
+                        .collect()
+                }).collect();
+                blocks.push(Block::Table(header, rows));
+            }
+        }
+        blocks
+    }
+
+    /// the form a root is looked up by, its singular noun if it can be one, otherwise whatever else it can be first
+    pub fn citation_form(&self, key: &str) -> Option<String> {
+        let root = self.roots.get(key)?;
+        let candidates = [
+            ("nom", Keyword::Nominative(key.to_string(), NounNumber::Singular, Case::Nominative)),
+            ("verb", Keyword::Verbal(key.to_string(), VerbForm::Infinitive, None, Polarity::Positive, Mood::Statement)),
+            ("adj", Keyword::Adjective(key.to_string())),
+            ("prep", Keyword::Prepositional(key.to_string())),
+            ("vadj", Keyword::VerbalAdjective(key.to_string())),
+            ("aadj", Keyword::AdjectAdjective(key.to_string())),
+        ];
+        candidates.into_iter()
+            .filter(|(keyword, _)| root.allows(keyword))
+            .find_map(|(_, k)| k.render(self).ok())
+    }
+
+    fn lexicon_reference(&self) -> Vec<Block> {
+        let mut conlang: Vec<Vec<String>> = vec![];
+        let mut english: Vec<Vec<String>> = vec![];
+        for (key, root) in &self.roots {
+            let citation = self.citation_form(key).unwrap_or_else(|| MISSING.to_string());
+            for word in root.english(key) {
+                english.push(vec![word.to_string(), citation.clone()]);
+            }
+            conlang.push(vec![citation, root.english(key).join(", "), root.field.clone().unwrap_or_default()]);
+        }
+        conlang.sort();
+        english.sort_by_key(|row| normalize(&row[0]));
+
+        vec![
+            Block::Heading(2, "Lexicon".to_string()),
+            Block::Heading(3, "Conlang to English".to_string()),
+            Block::Table(vec!["word".to_string(), "meaning".to_string(), "field".to_string()], conlang),
+            Block::Heading(3, "English to conlang".to_string()),
+            Block::Table(vec!["english".to_string(), "word".to_string()], english),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_stop_at_the_largest_power_that_fits() {
+        let mut lang = Lang::load("assets/lang").unwrap();
+        let numerals = lang.forms.numerals.as_mut().unwrap();
+        numerals.powers = (0..30).map(|p| format!("p{}", p)).collect();
+        let rows = lang.grammar_reference().into_iter().find_map(|b| match b {
+            Block::Table(header, rows) if header[0] == "number" => Some(rows),
+            _ => None,
+        }).unwrap();
+        // 0 to 10, then 100 up to 10^19, the last power of ten a u64 holds
+        assert_eq!(rows.len(), 11 + 18);
+        assert_eq!(rows.last().unwrap()[0], 10u64.pow(19).to_string());
+    }
+
+    #[test]
+    fn examples_that_dont_render_are_missing() {
+        let mut lang = Lang::load("assets/lang").unwrap();
+        // without the fallback molds five consonants fit none, so it parses but has no form
+        lang.forms.root_form.remove(FALLBACK_MOLDS);
+        let mut drip = lang.roots["water"].clone();
+        drip.consonants = "wdkpt".to_string();
+        drip.examples = vec!["nom{drip 0}".to_string()];
+        lang.roots.insert("drip".to_string(), drip);
+        lang.roots.get_mut("water").unwrap().examples = vec!["nom{water 0}".to_string()];
+
+        let items: Vec<String> = lang.root_reference().into_iter().flat_map(|b| match b {
+            Block::List(items) => items,
+            _ => vec![],
+        }).collect();
+        assert!(items.contains(&"example: nom{drip 0} = -".to_string()));
+        assert!(items.contains(&"example: nom{water 0} = wida".to_string()));
+    }
+}
@@ -1,146 +1,589 @@
-use std::fmt::format;
 use std::fs;
 use std::error::Error;
-use std::ops::IndexMut;
-
-use serde_yaml::{from_value, Value};
 
 use super::*;
 use super::super::debug::*;
 
 pub struct Lang {
-    forms: Value,
-    roots: Value,
+    pub(super) forms: Forms,
+    pub(super) roots: Roots,
+    pub(super) rules: RuleSet,
+}
+
+/// one meaningful piece of a rendered word, like a stem or a plural ending
+#[derive(Debug, Clone, PartialEq)]
+pub struct Morpheme {
+    pub form: String,
+    /// leipzig style label, e.g. "run.V" for a stem or "PL" for an ending
+    pub gloss: String,
+    /// whether it's an irregular form from roots.yaml rather than a filled mold
+    pub irregular: bool,
+}
+
+impl Morpheme {
+    pub(super) fn new<F, G>(form: F, gloss: G) -> Morpheme
+    where
+        F: Into<String>,
+        G: Into<String>,
+    {
+        Morpheme { form: form.into(), gloss: gloss.into(), irregular: false }
+    }
+
+    pub(super) fn irregular<F, G>(form: F, gloss: G) -> Morpheme
+    where
+        F: Into<String>,
+        G: Into<String>,
+    {
+        Morpheme { irregular: true, ..Morpheme::new(form, gloss) }
+    }
+}
+
+/// adds an ending after a stem, an empty ending has nothing to segment so it's fused into the stem's gloss
+pub(super) fn push_suffix(morphemes: &mut Vec<Morpheme>, form: &str, gloss: &str) {
+    match morphemes.last_mut() {
+        Some(last) if form.is_empty() => {
+            last.gloss.push('.');
+            last.gloss.push_str(gloss);
+        },
+        _ => morphemes.push(Morpheme::new(form, gloss)),
+    }
+}
+
+/// adds the verb ending for a subject of `person` and `number`, if the language has verb agreement at all
+fn push_agreement(morphemes: &mut Vec<Morpheme>, forms: &Forms, person: &Person, number: &Number) {
+    if let Some(ending) = forms.verb_agreement.get(&person_key(person, number, None, None)) {
+        push_suffix(morphemes, ending, &format!("{}{}", person.as_str(), number.gloss_label()));
+    }
 }
 
-fn get_type<T>(keys: Vec<&str>, value: &Value) -> Result<T, String>
-where T: serde::de::DeserializeOwned {
-    // {dbg!(&keys);
-    // dbg!(value);}
-    let mut spot = value;
-    for i in 0..(keys.len()-1) {
-        spot = &spot[keys[i]];
-    };
-    let t: T = from_value(spot[keys[keys.len()-1]].clone())
-        .ctx(format!("Invalid parameter {} in value", keys.join("/")))?;
-    Ok(t)
+/// a marker written onto a verb, particles are left for `Keyword::particles`
+fn push_marker(morphemes: &mut Vec<Morpheme>, marker: Option<&Marker>, gloss: &str) {
+    match marker.map(|m| (m.placement, m.form.as_str())) {
+        Some((MarkerPlacement::Prefix, form)) => morphemes.insert(0, Morpheme::new(form, gloss)),
+        Some((MarkerPlacement::Suffix, form)) => push_suffix(morphemes, form, gloss),
+        _ => (),
+    }
 }
 
 impl Keyword {
+    /// renders this keyword on its own, particles it'd bring along in a sentence are left out
     pub fn render(&self, lang: &Lang) -> Result<String, String> {
+        let (mut words, _) = lang.apply_rules(&[self.morphemes(lang)?]);
+        Ok(words.remove(0))
+    }
+
+    /// the pieces this keyword renders to, in order
+    pub fn morphemes(&self, lang: &Lang) -> Result<Vec<Morpheme>, String> {
+        let forms = &lang.forms;
+        let stem = |part: &str, root: &str, label: &str| lang.stem(part, root, label);
+        // a whole word standing in for a stem and the ending after it, glossed as one fused morpheme
+        let fused = |part: &str, root: &str, label: &str, inflection: &str, inflection_label: &str| {
+            lang.roots.get(root)
+                .and_then(|r| r.irregular(part, Some(inflection)))
+                .map(|form| {
+                    let gloss = [root, label, inflection_label].into_iter().filter(|l| !l.is_empty()).collect::<Vec<&str>>().join(".");
+                    vec![Morpheme::irregular(form, gloss)]
+                })
+        };
         Ok(match &self {
             Keyword::Adjective(root) => {
-                Keyword::format_base("adjective", root, lang)
+                stem("adjective", root, "ADJ")
                     .ctx(format!("Error formatting adjective with base `{}`", root))?
             },
-            Keyword::Nominative(root, plural) => {
-                format!(
-                    "{}{}",
-                    Keyword::format_base("nominative", root, lang)
-                        .ctx(format!("`Error formatting nominative with base {}`", root))?,
-                    if *plural {
-                        let ending: String = get_type(vec!["noun_plurality_suffix"], &lang.forms)
-                            .ctx("Error loading plurality ending, check forms.yaml")?;
-                        ending
-                    } else {
-                        "".to_string()
-                    }
-                )
+            Keyword::Nominative(root, number, case) => {
+                let case_form = forms.noun_cases.get(case.as_str());
+                let mold = case_form.and_then(|c| c.mold.as_deref()).unwrap_or("nominative");
+                // nouns are glossed by root alone, `NOM` would read as the case
+                let irregular = fused(mold, root, "", number.as_str(), number.gloss_label());
+                let mut morphemes = match irregular {
+                    Some(ref morphemes) => morphemes.clone(),
+                    None => stem(mold, root, "").ctx(format!("`Error formatting nominative with base {}`", root))?,
+                };
+                let suffix = match number {
+                    NounNumber::Singular => None,
+                    NounNumber::Dual => forms.noun_dual_suffix.as_ref(),
+                    NounNumber::Paucal => forms.noun_paucal_suffix.as_ref(),
+                    NounNumber::Plural => Some(&forms.noun_plurality_suffix),
+                };
+                if *number != NounNumber::Singular && irregular.is_none() {
+                    let suffix = suffix.ok_or(format!("No {} suffix in forms.yaml", number.as_name()))?;
+                    push_suffix(&mut morphemes, suffix, number.gloss_label());
+                }
+                match case_form {
+                    Some(c) => push_suffix(&mut morphemes, &c.suffix, case.gloss_label()),
+                    None if *case == Case::Nominative => (),
+                    None => return Err(format!("No `{}` case in forms.yaml", case.as_str())),
+                }
+                morphemes
             },
-            Keyword::Verbal(root, form) => {
-                format!(
-                    "{}{}",
-                    Keyword::format_base("verbal", root, lang)
-                    .ctx(format!("`Error formatting verbal with base {}`", root))?, 
-                    {
-                        let form_keys = match form {
-                            VerbForm::TensePresent => vec!["verb_tense_endings", "present"],
-                            VerbForm::TenseFuture => vec!["verb_tense_endings", "future"],
-                            VerbForm::TensePast => vec!["verb_tense_endings", "past"],
-                            VerbForm::Infinitive => vec!["verb_infinitive_suffix"],
+            Keyword::Verbal(root, form, subject, polarity, mood) => {
+                let mut morphemes = match fused("verbal", root, "V", form.as_str(), form.gloss_label()) {
+                    Some(morphemes) => morphemes,
+                    None => {
+                        let mut morphemes = stem("verbal", root, "V")
+                            .ctx(format!("`Error formatting verbal with base {}`", root))?;
+                        let ending = match form {
+                            VerbForm::TensePresent => &forms.verb_tense_endings.present,
+                            VerbForm::TenseFuture => &forms.verb_tense_endings.future,
+                            VerbForm::TensePast => &forms.verb_tense_endings.past,
+                            VerbForm::Infinitive => &forms.verb_infinitive_suffix,
+                            VerbForm::Imperative => &forms.verb_imperative_suffix,
                         };
-                        let ending: String = get_type(form_keys.clone(), &lang.forms)
-                            .ctx(format!("`Error getting verb ending with modifiers {}`", form_keys.join(" and ")))?;
-                        ending
-                    }
-                )
+                        push_suffix(&mut morphemes, ending, form.gloss_label());
+                        morphemes
+                    },
+                };
+                if let Some((person, number)) = subject {
+                    push_agreement(&mut morphemes, forms, person, number);
+                }
+                if *polarity == Polarity::Negative {
+                    push_marker(&mut morphemes, forms.negation.as_ref(), "NEG");
+                }
+                if *mood == Mood::Question {
+                    push_marker(&mut morphemes, forms.question.as_ref(), "Q");
+                }
+                morphemes
+            },
+            Keyword::VerbalAdjective(root) => {stem("verbal_adjective", root, "VADJ").ctx(format!("`Error formatting verbal adjective with base {}`", root))?},
+            Keyword::Prepositional(root) => {stem("prepositional", root, "PREP").ctx(format!("`Error formatting prepositional with base {}`", root))?},
+            Keyword::AdjectAdjective(root) => {stem("adject_adjective", root, "AADJ").ctx(format!("`Error formatting adject adjective with base {}`", root))?},
+
+            Keyword::CompletiveAspect => vec![Morpheme::new(&forms.verb_particles.aspect.completive, "CMPL")],
+            Keyword::ProgressiveAspect => vec![Morpheme::new(&forms.verb_particles.aspect.progressive, "PROG")],
+            Keyword::HabitualAspect => vec![Morpheme::new(&forms.verb_particles.aspect.habitual, "HAB")],
+            Keyword::PerfectAspect => vec![Morpheme::new(&forms.verb_particles.aspect.perfect, "PRF")],
+
+            Keyword::DefiniteArticle(deixis) => vec![Morpheme::new(forms.article.definite.get(deixis), format!("DEF.{}", deixis.gloss_label()))],
+            Keyword::IndefiniteArticle(deixis) => vec![Morpheme::new(forms.article.indefinite.get(deixis), format!("INDF.{}", deixis.gloss_label()))],
+            Keyword::DeicticSpatialNoun(deixis) => vec![Morpheme::new(
+                forms.deictic_nouns.spatial.get(deixis).ok_or(format!("`Error getting spatial noun with deixis {:?}`", deixis))?,
+                format!("LOC.{}", deixis.gloss_label()),
+            )],
+            Keyword::DeicticTemporalNoun(deixis) => vec![Morpheme::new(
+                forms.deictic_nouns.temporal.get(deixis).ok_or(format!("`Error getting temporal noun with deixis {:?}`", deixis))?,
+                format!("TIME.{}", deixis.gloss_label()),
+            )],
+            Keyword::Interrogative(word) => vec![Morpheme::new(
+                forms.question_words.get(word).ok_or(format!("`Error getting question word {}`", word))?,
+                format!("{}.Q", word),
+            )],
+            Keyword::Numeral(number) => forms.numerals.as_ref()
+                .ok_or("No numerals in forms.yaml".to_string())?
+                .morphemes(*number)?,
+            Keyword::Pronoun(person, number, clusivity, formality) => {
+                let (form, clusivity, formality) = forms.pronoun(person, number, clusivity.as_ref(), formality.as_ref())
+                    .ok_or(format!("`Error getting pronoun {}` (no pronouns in forms.yaml)", person_key(person, number, clusivity.as_ref(), formality.as_ref())))?;
+                // only what the language actually marks makes it into the gloss
+                let mut gloss = format!("{}{}", person.as_str(), number.gloss_label());
+                for label in [clusivity.map(|c| c.gloss_label()), formality.map(|f| f.gloss_label())].into_iter().flatten() {
+                    gloss.push('.');
+                    gloss.push_str(label);
+                }
+                vec![Morpheme::new(form, gloss)]
+            },
+
+            Keyword::Defined(name, root, values) => {
+                let definition = forms.keywords.get(name).ok_or(format!("No keyword `{}` in forms.yaml", name))?;
+                let mut morphemes = match (root, &definition.mold) {
+                    (Some(root), Some(mold)) => stem(mold, root, &definition.gloss)
+                        .ctx(format!("Error formatting `{}` with base {}", name, root))?,
+                    _ => vec![Morpheme::new(&definition.form, &definition.gloss)],
+                };
+                for (parameter, value) in definition.parameters.iter().zip(values) {
+                    let affix = parameter.values.get(value).ok_or(format!("`{}` can't be {} `{}`", name, parameter.name, value))?;
+                    push_suffix(&mut morphemes, &affix.form, &affix.gloss);
+                }
+                morphemes
             },
-            Keyword::VerbalAdjective(root) => {Keyword::format_base("verbal_adjective", root, lang).ctx(format!("`Error formatting verbal adjective with base {}`", root))?},
-            Keyword::Prepositional(root) => {Keyword::format_base("prepositional", root, lang).ctx(format!("`Error formatting prepositional with base {}`", root))?},
-            Keyword::AdjectAdjective(root) => {Keyword::format_base("adject_adjective", root, lang).ctx(format!("`Error formatting adject adjective with base {}`", root))?},
-
-            Keyword::CompletiveAspect => get_type(vec!["verb_particles", "aspect", "completive"], &lang.forms).ctx("Error getting completive aspect marker")?,
-            Keyword::ProgressiveAspect => get_type(vec!["verb_particles", "aspect", "progressive"], &lang.forms).ctx("Error getting progressive aspect marker")?,
-            Keyword::HabitualAspect => get_type(vec!["verb_particles", "aspect", "habitual"], &lang.forms).ctx("Error getting habitual aspect marker")?,
-            Keyword::PerfectAspect => get_type(vec!["verb_particles", "aspect", "perfect"], &lang.forms).ctx("Error getting perfect aspect marker")?,
-
-            Keyword::DefiniteArticle(deixis) => get_type(vec!["article", "definite", deixis.as_str()], &lang.forms).ctx(format!("`Error getting definite article with deixis {:?}`", deixis))?, 
-            Keyword::IndefiniteArticle(deixis) => get_type(vec!["article", "indefinite", deixis.as_str()], &lang.forms).ctx(format!("`Error getting indefinite article with deixis {:?}`", deixis))?, 
-            Keyword::DeicticSpatialNoun(deixis) => get_type(vec!["deictic_nouns", "spatial", deixis.as_str()], &lang.forms).ctx(format!("`Error getting spatial noun with deixis {:?}`", deixis))?, 
-            Keyword::DeicticTemporalNoun(deixis) => get_type(vec!["deictic_nouns", "temporal", deixis.as_str()], &lang.forms).ctx(format!("`Error getting temporal noun with deixis {:?}`", deixis))?, 
         })
     }
 
-    fn format_base(of_type: &str, root: &str, lang: &Lang) -> Result<String, String> {
-        let cons: String = get_type(vec![root], &lang.roots).ctx("error")?;
-        let mold: String = get_type(vec![
-            "root_form",
-            cons.len().to_string().as_str(),
-            of_type
-        ], &lang.forms).ctx("error")?; 
-        let cons = cons.as_str(); let mold = mold.as_str();
+    /// words this keyword brings along in a sentence, like a negation particle,
+    /// the ones that go before it and the ones that go after it
+    pub fn particles(&self, lang: &Lang) -> (Vec<Vec<Morpheme>>, Vec<Vec<Morpheme>>) {
+        let (mut before, mut after) = (vec![], vec![]);
+        if let Keyword::Verbal(_, _, _, polarity, mood) = self {
+            let markers = [
+                (*polarity == Polarity::Negative, lang.forms.negation.as_ref(), "NEG"),
+                (*mood == Mood::Question, lang.forms.question.as_ref(), "Q"),
+            ];
+            for (on, marker, gloss) in markers {
+                let Some(marker) = marker.filter(|_| on) else {continue};
+                match marker.placement {
+                    MarkerPlacement::Before => before.push(vec![Morpheme::new(&marker.form, gloss)]),
+                    MarkerPlacement::After => after.push(vec![Morpheme::new(&marker.form, gloss)]),
+                    _ => (),
+                }
+            }
+        }
+        (before, after)
+    }
+
+    /// `part` names the mold, like "nominative"
+    pub(super) fn format_base(part: &str, root: &str, lang: &Lang) -> Result<String, String> {
+        let cons = &lang.roots.get(root).ok_or(format!("No root `{}` in roots.yaml", root))?.consonants;
+        let length = consonant_count(cons);
+        let (_, molds) = lang.forms.molds_for(length)
+            .ok_or(format!("No molds for roots of {} consonant(s)", length))?;
+        let cons = cons.as_str();
+        let mold = molds.get(part).ok_or(format!("No `{}` mold for roots of {} consonant(s)", part, length))?.as_str();
         
         Keyword::format_patterns(cons, mold)  
     }
 
-    fn format_patterns(cons_pattern: &str, mold_pattern: &str) -> Result<String, String> {
-        let mut out_string: Vec<char> = mold_pattern.chars().collect();
-        for i in 0..cons_pattern.len() {
-            let out_string_str: String = out_string.iter().cloned().collect();
-            let replace = match out_string_str.find("-") {
-                Some(u) => u,
-                None => return Err("Pattern is missing one or more indicators for consonant patters (denoted by `-`)!".to_string())
-            };
-            out_string[replace] = match cons_pattern.chars().nth(i) {
-                Some(c) => c,
-                None => return Err("An unknown error has occured".to_string())
-            };
-        }
-
-        let out: String = out_string.into_iter().collect();
-        Ok(out)
+    /// fills the slots of the mold with the consonants (and vowels, if any) of the root, see `Mold`
+    ///
+    /// both sides are walked by grapheme, so `ʃ` or a decomposed `á` count as one letter,
+    /// and any combining marks written on a slot (like `-́`) end up on the letter that replaces it
+    pub(super) fn format_patterns(cons_pattern: &str, mold_pattern: &str) -> Result<String, String> {
+        Mold::parse(mold_pattern)?.fill(cons_pattern)
     }
 }
 
-pub fn render_keywords(keywords: Vec<Keyword>, lang: &Lang) -> String {
+/// how many consonants a root has, counting graphemes rather than bytes or chars
+pub fn consonant_count(root: &str) -> usize {
+    split_root(root).0.len()
+}
+
+/// one word of a rendered sentence, along with where it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentenceWord {
+    pub surface: String,
+    /// the keyword it belongs to, a verb agreeing with its subject having that filled in
+    pub keyword: Keyword,
+    /// the gloss label of what it marks when it's a particle the keyword brings along, like `NEG`
+    pub particle: Option<String>,
+}
+
+/// the keywords with every verb that doesn't say its person and number agreeing with the last subject before it
+pub fn with_agreement(keywords: &[Keyword]) -> Vec<Keyword> {
+    let mut subject: Option<(Person, Number)> = None;
+    keywords.iter()
+        .map(|keyword| {
+            if let Some(s) = keyword.as_subject() {
+                subject = Some(s);
+            }
+            // infinitives and orders don't agree with anything unless told to
+            match (keyword, &subject) {
+                (Keyword::Verbal(root, form, None, polarity, mood), Some(s)) if !matches!(form, VerbForm::Infinitive | VerbForm::Imperative) => {
+                    Keyword::Verbal(root.clone(), form.clone(), Some(s.clone()), polarity.clone(), mood.clone())
+                },
+                _ => keyword.clone(),
+            }
+        })
+        .collect()
+}
+
+/// errors if anything in the sentence has no form in `lang`, like a root missing from roots.yaml
+pub fn render_keywords(keywords: Vec<Keyword>, lang: &Lang) -> Result<String, String> {
+    let morphemes = lang.sentence_morphemes(&keywords)?;
+
+    // rules run over the whole sentence at once so they can reach across words
     let mut out_string = String::new();
-    for keyword in keywords {
-        let rendered = match keyword.render(lang) {
-            Ok(s) => s,
-            Err(e) => panic!("{}", e)
-        };
-        out_string.push_str(&rendered.as_str());
+    for rendered in lang.apply_rules(&morphemes).0 {
+        out_string.push_str(rendered.as_str());
         out_string.push(' ');
     }
-    out_string
+    Ok(out_string)
 }
 
 impl Lang {
+    /// the morphemes of every word in a sentence, with whatever the words do to each other,
+    /// like adjectives taking the case of the noun before them
+    /// or verbs without a person and number agreeing with the last subject before them
+    ///
+    /// agreement goes by the order the markup was written in, the words come back in the language's own order
+    pub fn sentence_morphemes(&self, keywords: &[Keyword]) -> Result<Vec<Vec<Morpheme>>, String> {
+        // one group of words per keyword, so they can be reordered together
+        let mut sentence: Vec<Vec<Vec<Morpheme>>> = vec![];
+        let mut case: Option<&Case> = None;
+        for (keyword, agreeing) in keywords.iter().zip(with_agreement(keywords)) {
+            let mut morphemes = agreeing.morphemes(self)?;
+            match keyword {
+                Keyword::Nominative(_, _, c) => case = Some(c),
+                Keyword::Adjective(_) | Keyword::VerbalAdjective(_) | Keyword::AdjectAdjective(_) if self.forms.adjective_case_agreement => {
+                    // a case mold only goes on nouns, adjectives agree with the ending alone
+                    if let Some((c, form)) = case.and_then(|c| Some((c, self.forms.noun_cases.get(c.as_str())?))) {
+                        push_suffix(&mut morphemes, &form.suffix, c.gloss_label());
+                    }
+                },
+                _ => case = None,
+            }
+            let (before, after) = keyword.particles(self);
+            sentence.push([before, vec![morphemes], after].concat());
+        }
+        Ok(self.word_order(keywords).into_iter().flat_map(|i| std::mem::take(&mut sentence[i])).collect())
+    }
+
+    /// a sentence rendered word by word, in the language's own order, each word with the keyword it comes from
+    pub fn sentence_words(&self, keywords: &[Keyword]) -> Result<Vec<SentenceWord>, String> {
+        let keywords = with_agreement(keywords);
+        let morphemes = self.sentence_morphemes(&keywords)?;
+        let (surfaces, _) = self.apply_rules(&morphemes);
+
+        // the same order `Lang::sentence_morphemes` puts the words in
+        let mut origins: Vec<(&Keyword, Option<String>)> = vec![];
+        for i in self.word_order(&keywords) {
+            let (before, after) = keywords[i].particles(self);
+            let particle = |p: Vec<Morpheme>| (&keywords[i], p.into_iter().next().map(|m| m.gloss));
+            origins.extend(before.into_iter().map(particle));
+            origins.push((&keywords[i], None));
+            origins.extend(after.into_iter().map(particle));
+        }
+
+        Ok(surfaces.into_iter()
+            .zip(origins)
+            .map(|(surface, (keyword, particle))| SentenceWord { surface, keyword: keyword.clone(), particle })
+            .collect())
+    }
+
     pub fn load(static_path: &str) -> Result<Lang, Box<dyn Error>> {
         let forms_str: String = fs::read_to_string(format!("{}/forms.yaml", static_path)).ctx(format!("Failed to load forms.yaml from {}", static_path))?; 
         let roots_str: String = fs::read_to_string(format!("{}/roots.yaml", static_path)).ctx(format!("Failed to load roots.yaml  from {}", static_path))?; 
         
-        let forms: Value = serde_yaml::from_str(&forms_str).ctx("Failed to parse forms.yaml")?; 
-        let roots: Value = serde_yaml::from_str(&roots_str).ctx("Failed to parse roots.yaml")?; 
+        Lang::from_yaml(&forms_str, &roots_str)
+    }
+
+    /// parses and checks the contents of forms.yaml and roots.yaml
+    pub fn from_yaml(forms_str: &str, roots_str: &str) -> Result<Lang, Box<dyn Error>> {
+        let roots = roots_from_yaml(roots_str)?;
+        let forms = Forms::from_yaml(forms_str, &roots)?;
+        // already checked by `Forms::from_yaml`
+        let rules = RuleSet::new(&forms.phonology).map_err(|(_, e)| e)?;
 
-        Ok(Lang {
+        let lang = Lang {
             forms,
             roots,
-        })
+            rules,
+        };
+        lang.check_dictionary(roots_str)?;
+        Ok(lang)
+    }
+
+    /// parses markup into keywords, including the ones this language declares in forms.yaml,
+    /// and turning down anything it can't render like cases it doesn't have
+    pub fn parse(&self, text: &str) -> Result<Vec<Keyword>, Vec<MarkupError>> {
+        to_object_with(text, Some(self))
+    }
+
+    /// like `Lang::parse`, along with the span each keyword was written at
+    pub fn parse_spans(&self, text: &str) -> Result<Vec<(Keyword, Span)>, Vec<MarkupError>> {
+        to_object_spans(text, Some(self))
+    }
+
+    /// like `Lang::parse`, filling in `$name` parameters from `variables` first
+    pub fn parse_with(&self, text: &str, variables: &dyn Variables) -> Result<Vec<Keyword>, Vec<MarkupError>> {
+        Ok(to_object_spans_with(text, Some(self), variables)?.into_iter().map(|(k, _)| k).collect())
+    }
+
+    pub fn render(&self, text: &str) -> Result<String, Vec<MarkupError>> {
+        let keywords = self.parse_spans(text)?;
+        self.render_spans(text, keywords)
+    }
+
+    /// like `Lang::render`, filling in `$name` parameters from `variables` first,
+    /// e.g. `artDef{$deixis} nom{$item 0}` for whatever the player is looking at
+    pub fn render_with(&self, text: &str, variables: &dyn Variables) -> Result<String, Vec<MarkupError>> {
+        let keywords = to_object_spans_with(text, Some(self), variables)?;
+        self.render_spans(text, keywords)
+    }
+
+    fn render_spans(&self, text: &str, keywords: Vec<(Keyword, Span)>) -> Result<String, Vec<MarkupError>> {
+        render_keywords(keywords.iter().map(|(k, _)| k.clone()).collect(), self)
+            .map_err(|e| self.unrenderable(text, &keywords, e))
+    }
+
+    /// an error rendering `keywords` as a markup error, put down to the first keyword that has no form, or the whole markup
+    pub(super) fn unrenderable(&self, text: &str, keywords: &[(Keyword, Span)], error: String) -> Vec<MarkupError> {
+        let span = keywords.iter()
+            .find(|(k, _)| k.morphemes(self).is_err())
+            .map_or(Span { start: 0, end: text.len(), line: 1, column: 1 }, |(_, s)| *s);
+        vec![MarkupError::new(MarkupErrorKind::Unrenderable, span, error, "the language files have no form for it, check forms.yaml and roots.yaml")]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (root, mold, expected) with non ascii letters on either side, decomposed accents included
+    const MULTIBYTE_CORPUS: [(&str, &str, &str); 10] = [
+        ("ʃ", "-a", "ʃa"),
+        ("ʃð", "-i-a", "ʃiða"),
+        ("ŋkʃ", "-un-i-a", "ŋunkiʃa"),
+        ("wd", "-á-ar", "wádar"),
+        ("wd", "-a\u{301}-ar", "wa\u{301}dar"),
+        ("ðŋ", "-ø-œ", "ðøŋœ"),
+        ("n\u{303}t", "-a-", "n\u{303}at"),
+        ("kt", "-\u{301}a-", "k\u{301}at"),
+        ("ħʕ", "-ā-ī", "ħāʕī"),
+        ("ʔ", "ə-ə", "əʔə"),
+    ];
+
+    #[test]
+    fn format_patterns_handles_multibyte_roots_and_molds() {
+        for (root, mold, expected) in MULTIBYTE_CORPUS {
+            assert_eq!(Keyword::format_patterns(root, mold).as_deref(), Ok(expected), "root `{}` in mold `{}`", root, mold);
+        }
+    }
+
+    #[test]
+    fn consonant_count_counts_graphemes() {
+        assert_eq!(consonant_count("ʃð"), 2);
+        assert_eq!(consonant_count("n\u{303}t"), 2);
+        assert_eq!(consonant_count("ŋkʃ"), 3);
+    }
+
+    #[test]
+    fn format_patterns_rejects_mismatched_slots() {
+        assert!(Keyword::format_patterns("ʃð", "-a").is_err());
+        assert!(Keyword::format_patterns("ʃ", "-a-").is_err());
     }
 
-    pub fn render(&self, text: &str) -> String {
-        let object = to_object(text);
-        render_keywords(object, self)
+    #[test]
+    fn format_patterns_fills_named_slots() {
+        let cases = [
+            // reordering, gemination and dropping
+            ("ktb", "{C1}a{C2}{C2}a{C3}", "kattab"),
+            ("ktb", "{C3}i{C1}", "bik"),
+            ("ktb", "ma{C1}{C2}u{C3}", "maktub"),
+            // reduplication, counting from the end
+            ("ktb", "{C1}a{C-1}{C-1}a", "kabba"),
+            // a vowel melody given with the root
+            ("ktb/ai", "{C1}{V1}{C2}{V2}{C3}", "katib"),
+            // the rest of a long root spread out
+            ("trjmn", "{C1}a{C*:a}i{C-1}", "tarajamin"),
+            ("tr", "{C1}a{C*}", "tar"),
+            // named and anonymous slots together
+            ("ktb", "-a-{C3}{C3}", "katbb"),
+            ("ʃð", "{C2}\u{301}a{C1}", "ð\u{301}aʃ"),
+        ];
+        for (root, mold, expected) in cases {
+            assert_eq!(Keyword::format_patterns(root, mold).as_deref(), Ok(expected), "root `{}` in mold `{}`", root, mold);
+        }
+    }
+
+    #[test]
+    fn format_patterns_rejects_bad_named_slots() {
+        assert!(Keyword::format_patterns("kt", "{C3}a").is_err());
+        assert!(Keyword::format_patterns("kt", "{C-3}a").is_err());
+        assert!(Keyword::format_patterns("kt", "{V1}{C1}").is_err());
+        assert!(Keyword::format_patterns("kt", "{X1}").is_err());
+        assert!(Keyword::format_patterns("kt", "{C1").is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn render_keywords_errors_instead_of_panicking() {
+        let mut lang = Lang::load("assets/lang").unwrap();
+        let keywords = vec![Keyword::Nominative("nosuchroot".to_string(), NounNumber::Singular, Case::Nominative)];
+        assert!(render_keywords(keywords, &lang).is_err());
+        assert_eq!(lang.render("nom{water 0}").unwrap().trim(), "wida");
+
+        // no molds left for two consonant roots like water's
+        lang.forms.root_form.retain(|length, _| length != "2" && length != "n");
+        let text = "aspProg nom{water 0}";
+        let errors = lang.render(text).unwrap_err();
+        assert_eq!(errors.iter().map(|e| e.kind).collect::<Vec<_>>(), vec![MarkupErrorKind::Unrenderable]);
+        // put down to the keyword with no form
+        assert_eq!(&text[errors[0].span.start..errors[0].span.end], "nom{water 0}");
+    }
+
+    #[test]
+    fn pronouns_fall_back_on_person_and_number() {
+        let mut lang = Lang::load("assets/lang").unwrap();
+        let pronoun = |lang: &Lang, markup: &str| {
+            let keyword = lang.parse(markup).unwrap().remove(0);
+            let morpheme = keyword.morphemes(lang).unwrap().remove(0);
+            (morpheme.form, morpheme.gloss)
+        };
+        assert_eq!(pronoun(&lang, "pron{1 sg}"), ("mi".to_string(), "1SG".to_string()));
+        assert_eq!(pronoun(&lang, "pron{2 sg form}"), ("vasu".to_string(), "2SG.POL".to_string()));
+        assert_eq!(pronoun(&lang, "pron{1 pl incl}"), ("tanu".to_string(), "1PL.INCL".to_string()));
+        // features the language doesn't mark are left out of the form and the gloss
+        assert_eq!(pronoun(&lang, "pron{2 sg fam}"), ("tu".to_string(), "2SG".to_string()));
+        assert_eq!(pronoun(&lang, "pron{1 pl excl}"), ("nos".to_string(), "1PL".to_string()));
+        assert_eq!(pronoun(&lang, "pron{1 pl incl form}"), ("tanu".to_string(), "1PL.INCL".to_string()));
+
+        lang.forms.pronouns.clear();
+        assert!(lang.render("pron{1 sg}").is_err());
+    }
+
+    #[test]
+    fn verbs_agree_with_their_subject() {
+        let mut lang = Lang::load("assets/lang").unwrap();
+        let ending = |lang: &Lang, person, number| {
+            let keyword = Keyword::Verbal("sleep".to_string(), VerbForm::TensePresent, Some((person, number)), Polarity::Positive, Mood::Statement);
+            keyword.morphemes(lang).unwrap().pop().map(|m| (m.form, m.gloss)).unwrap()
+        };
+        assert_eq!(ending(&lang, Person::First, Number::Singular), ("m".to_string(), "1SG".to_string()));
+        assert_eq!(ending(&lang, Person::Second, Number::Plural), ("te".to_string(), "2PL".to_string()));
+        // the subject in a sentence is what picks the ending
+        let last_gloss = |markup: &str| lang.sentence_morphemes(&lang.parse(markup).unwrap()).unwrap().pop().unwrap().pop().unwrap().gloss;
+        assert_eq!(last_gloss("pron{1 sg} verb{sleep pres}"), "1SG");
+        assert_eq!(last_gloss("nom{cat 1} verb{sleep pres}"), "3PL");
+        assert_eq!(last_gloss("pron{1 sg} verb{sleep pres 2 pl}"), "2PL");
+
+        // no ending for a person and number just leaves the verb as it is
+        lang.forms.verb_agreement.remove("2pl");
+        assert_eq!(ending(&lang, Person::Second, Number::Plural).1, VerbForm::TensePresent.gloss_label());
+    }
+
+    #[test]
+    fn negation_and_questions_go_where_they_are_placed() {
+        let mut lang = Lang::load("assets/lang").unwrap();
+        let verb = lang.render("verb{sleep pres}").unwrap().trim().to_string();
+        assert_eq!(lang.render("verb{sleep pres neg}").unwrap().trim(), format!("ma {}", verb));
+        assert_eq!(lang.render("verb{sleep pres q}").unwrap().trim(), format!("{} ka", verb));
+        assert_eq!(lang.render("verb{sleep pres neg q}").unwrap().trim(), format!("ma {} ka", verb));
+
+        let marker = |form: &str, placement| Some(Marker { form: form.to_string(), placement });
+        lang.forms.negation = marker("mu", MarkerPlacement::Prefix);
+        lang.forms.question = marker("ki", MarkerPlacement::Suffix);
+        assert_eq!(lang.render("verb{sleep pres neg}").unwrap().trim(), format!("mu{}", verb));
+        assert_eq!(lang.render("verb{sleep pres neg q}").unwrap().trim(), format!("mu{}ki", verb));
+        let glosses: Vec<String> = Keyword::Verbal("sleep".to_string(), VerbForm::TensePresent, None, Polarity::Negative, Mood::Question)
+            .morphemes(&lang).unwrap().into_iter().map(|m| m.gloss).collect();
+        assert_eq!(glosses.first().map(String::as_str), Some("NEG"));
+        assert_eq!(glosses.last().map(String::as_str), Some("Q"));
+
+        lang.forms.negation = marker("mu", MarkerPlacement::After);
+        lang.forms.question = marker("ki", MarkerPlacement::Before);
+        assert_eq!(lang.render("verb{sleep pres neg q}").unwrap().trim(), format!("ki {} mu", verb));
+
+        // a language that marks neither won't take them
+        lang.forms.negation = None;
+        lang.forms.question = None;
+        assert!(lang.render("verb{sleep pres neg}").is_err());
+        assert!(lang.render("verb{sleep pres q}").is_err());
+    }
+
+    #[test]
+    fn renders_imperatives_and_question_words() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let stem = Keyword::format_base("verbal", "sleep", &lang).unwrap();
+        assert_eq!(lang.render("verb{sleep imp}").unwrap().trim(), format!("{}{}", stem, lang.forms.verb_imperative_suffix));
+        assert_eq!(lang.render("wh{who}").unwrap().trim(), "kim");
+        assert_eq!(lang.render("wh{why}").unwrap().trim(), "kenu");
+        let morphemes = Keyword::Interrogative("what".to_string()).morphemes(&lang).unwrap();
+        assert_eq!((morphemes[0].form.as_str(), morphemes[0].gloss.as_str()), ("ke", "what.Q"));
+        assert!(lang.render("wh{whence}").is_err());
+    }
+
+    #[test]
+    fn irregular_forms_stand_in_for_a_mold_or_a_mold_and_ending() {
+        let mut lang = Lang::load("assets/lang").unwrap();
+        // `nominative.pl` replaces the stem and the plural suffix, but only in the plural
+        assert_eq!(lang.render("nom{cat 1}").unwrap().trim(), "kitu");
+        assert_eq!(lang.render("nom{cat 0}").unwrap().trim(), "kita");
+        // `verbal.past` only in the past
+        assert_eq!(lang.render("pron{3 sg} verb{be past}").unwrap().trim(), "lo wos");
+        assert_eq!(lang.render("pron{3 sg} verb{be pres}").unwrap().trim(), "lo para");
+
+        // a bare mold name replaces the stem alone, endings still go on
+        lang.roots.get_mut("water").unwrap().irregular.insert("nominative".to_string(), "aqa".to_string());
+        assert_eq!(lang.render("nom{water 0}").unwrap().trim(), "aqa");
+        assert_eq!(lang.render("nom{water 0 loc}").unwrap().trim(), "aqane");
+        let morphemes = Keyword::Nominative("water".to_string(), NounNumber::Plural, Case::Nominative).morphemes(&lang).unwrap();
+        assert_eq!(morphemes.iter().map(|m| (m.form.as_str(), m.irregular)).collect::<Vec<_>>(), vec![("aqa", true), ("at", false)]);
+    }
+}
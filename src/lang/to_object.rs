@@ -1,14 +1,30 @@
-use std::vec;
+use std::collections::BTreeMap;
 
-#[derive(Debug)]
+use super::*;
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum VerbForm {
     TensePresent,
     TenseFuture,
     TensePast,
-    Infinitive
+    Infinitive,
+    Imperative,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Polarity {
+    Positive,
+    Negative,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mood {
+    Statement,
+    /// a yes or no question, wh questions use `Keyword::Interrogative` instead
+    Question,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Deixis {
     NonSpatial,
     Proximal,
@@ -16,12 +32,64 @@ pub enum Deixis {
     Distal
 }
 
-#[derive(Debug)]
+/// what a noun is doing in the sentence, which endings mark it is up to the language
+#[derive(Debug, Clone, PartialEq)]
+pub enum Case {
+    Nominative,
+    Accusative,
+    Genitive,
+    Dative,
+    Locative,
+    Instrumental,
+    Ablative,
+    Allative,
+    Comitative,
+    Vocative,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Person {
+    First,
+    Second,
+    Third,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Number {
+    Singular,
+    Plural,
+}
+
+/// how many of a noun there are, the plural is for anything the language has no closer number for
+#[derive(Debug, Clone, PartialEq)]
+pub enum NounNumber {
+    Singular,
+    /// exactly two
+    Dual,
+    /// a few
+    Paucal,
+    Plural,
+}
+
+/// whether a first person plural takes in whoever is being spoken to
+#[derive(Debug, Clone, PartialEq)]
+pub enum Clusivity {
+    Inclusive,
+    Exclusive,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Formality {
+    Familiar,
+    Formal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Keyword {
     // first string in these spots is pretty much just the root
     Adjective(String),
-    Nominative(String, bool), // bool plural
-    Verbal(String, VerbForm),
+    Nominative(String, NounNumber, Case),
+    Verbal(String, VerbForm, Option<(Person, Number)>, Polarity, Mood), // the subject it agrees with, if given
     VerbalAdjective(String),
     Prepositional(String),
     AdjectAdjective(String),
@@ -37,48 +105,505 @@ pub enum Keyword {
 
     DeicticSpatialNoun(Deixis),
     DeicticTemporalNoun(Deixis),
+
+    Pronoun(Person, Number, Option<Clusivity>, Option<Formality>),
+    Interrogative(String), // a question word from forms.yaml, like "who"
+    Numeral(u64),
+
+    // declared under `keywords` in forms.yaml: the keyword, its root if it has a mold, and one value per parameter
+    Defined(String, Option<String>, Vec<String>),
+}
+
+/// a single symbol read out of the markup, along with where it came from
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub text: String,
+    pub span: Span,
 }
 
-pub fn keyword_from_string(keyword: &str, parameters: Vec<String>) -> Keyword {
-    fn deixis_from_string(deixis_string: String, allow_nonspatial: bool) -> Deixis {
-        match deixis_string.as_str() {
-            "nspac" => if allow_nonspatial {Deixis::NonSpatial} else {panic!("you may not use non spatial deixis here")},
-            "prox" => Deixis::Proximal,
-            "imm" => Deixis::Immediate,
-            "dist" => Deixis::Distal,
-            _ => panic!("no such valid deixis {} in this context", deixis_string)
+pub const KEYWORDS: [&str; 17] = [
+    "adj", "nom", "verb", "vadj", "prep", "aadj",
+    "aspComp", "aspProg", "aspHabt", "aspPerf",
+    "artDef", "artIndef", "dNounSpac", "dNounTemp",
+    "pron", "wh", "num",
+];
+
+/// with a `lang`, the keywords it declares itself are tried after the built in ones,
+/// and parameters like cases are checked against what it actually has
+pub fn keyword_from_string(keyword: &Token, parameters: &[Token], lang: Option<&Lang>) -> Result<Keyword, MarkupError> {
+    // makes sure there are exactly as many parameters as `names`, and hands them back
+    fn expect<'t>(keyword: &Token, parameters: &'t [Token], names: &[&str]) -> Result<&'t [Token], MarkupError> {
+        let usage = if names.is_empty() {
+            keyword.text.clone()
+        } else {
+            format!("{}{{{}}}", keyword.text, names.join(" "))
+        };
+        if parameters.len() < names.len() {
+            return Err(MarkupError::new(
+                MarkupErrorKind::MissingParameter,
+                keyword.span,
+                format!("`{}` is missing the parameter `{}`", keyword.text, names[parameters.len()]),
+                format!("write it as `{}`", usage),
+            ));
         }
+        if parameters.len() > names.len() {
+            return Err(MarkupError::new(
+                MarkupErrorKind::UnexpectedParameter,
+                parameters[names.len()].span,
+                format!("`{}` takes {} parameter(s) but was given {}", keyword.text, names.len(), parameters.len()),
+                format!("write it as `{}`", usage),
+            ));
+        }
+        Ok(parameters)
     }
-    match keyword {
-        "adj" => Keyword::Adjective(parameters[0].clone()),
-        "nom" => Keyword::Nominative(parameters[0].clone(), {
-            if parameters[1] == "1" {true} else {false}
-        }),
-        "verb" => Keyword::Verbal(parameters[0].clone(), {
-            match parameters[1].as_str() {
+
+    // a root, possibly compounded or derived, see `RootSpec`
+    fn root_from(keyword: &Token, token: &Token, lang: Option<&Lang>) -> Result<String, MarkupError> {
+        match lang.map(|l| l.check_root(&keyword.text, &token.text)) {
+            Some(Err((message, hint))) => Err(MarkupError::new(MarkupErrorKind::InvalidParameter, token.span, message, hint)),
+            _ => Ok(token.text.clone()),
+        }
+    }
+
+    fn deixis_from_string(deixis: &Token, allow_nonspatial: bool) -> Result<Deixis, MarkupError> {
+        let valid = if allow_nonspatial {"nspac, prox, imm or dist"} else {"prox, imm or dist"};
+        match deixis.text.as_str() {
+            "nspac" if allow_nonspatial => Ok(Deixis::NonSpatial),
+            "prox" => Ok(Deixis::Proximal),
+            "imm" => Ok(Deixis::Immediate),
+            "dist" => Ok(Deixis::Distal),
+            "nspac" => Err(MarkupError::new(
+                MarkupErrorKind::InvalidDeixis,
+                deixis.span,
+                "non spatial deixis is not allowed here",
+                format!("use one of {}", valid),
+            )),
+            _ => Err(MarkupError::new(
+                MarkupErrorKind::InvalidDeixis,
+                deixis.span,
+                format!("no such deixis `{}`", deixis.text),
+                format!("use one of {}", valid),
+            )),
+        }
+    }
+
+    Ok(match keyword.text.as_str() {
+        "adj" => Keyword::Adjective(root_from(keyword, &expect(keyword, parameters, &["root"])?[0], lang)?),
+        "nom" => {
+            // the case is optional, leaving it out means nominative
+            let names: &[&str] = if parameters.len() > 2 {&["root", "number", "case"]} else {&["root", "number"]};
+            let p = expect(keyword, parameters, names)?;
+            let number = noun_number_from_string(&p[1], lang)?;
+            let case = match p.get(2) {
+                Some(token) => case_from_string(token, lang)?,
+                None => Case::Nominative,
+            };
+            Keyword::Nominative(root_from(keyword, &p[0], lang)?, number, case)
+        },
+        "verb" => {
+            // `neg` and `q` can go at the very end, in either order
+            let mut p = parameters;
+            let (mut polarity, mut mood) = (Polarity::Positive, Mood::Statement);
+            while p.len() > 2 {
+                let last = &p[p.len() - 1];
+                let (section, has_marker) = match last.text.as_str() {
+                    "neg" if polarity == Polarity::Positive => {
+                        polarity = Polarity::Negative;
+                        ("negation", lang.map(|l| l.forms.negation.is_some()))
+                    },
+                    "q" if mood == Mood::Statement => {
+                        mood = Mood::Question;
+                        ("question", lang.map(|l| l.forms.question.is_some()))
+                    },
+                    _ => break,
+                };
+                if has_marker == Some(false) {
+                    return Err(MarkupError::new(
+                        MarkupErrorKind::InvalidParameter,
+                        last.span,
+                        format!("this language has no {} marker", section),
+                        format!("add `{}` to forms.yaml", section),
+                    ));
+                }
+                p = &p[..p.len() - 1];
+            }
+
+            // the subject's person and number are optional, for verbs agreeing with it
+            let names: &[&str] = if p.len() > 2 {&["root", "form", "person", "number"]} else {&["root", "form"]};
+            let p = expect(keyword, p, names)?;
+            let subject = match p.get(2..4) {
+                Some([person, number]) => Some((person_from_string(person)?, number_from_string(number)?)),
+                _ => None,
+            };
+            let form = match p[1].text.as_str() {
                 "pres" => VerbForm::TensePresent,
                 "fut" => VerbForm::TenseFuture,
                 "past" => VerbForm::TensePast,
                 "inf" => VerbForm::Infinitive,
-                _ => panic!("unknown parameter {} for verb form", parameters[1])
+                "imp" => VerbForm::Imperative,
+                _ => return Err(MarkupError::new(
+                    MarkupErrorKind::InvalidParameter,
+                    p[1].span,
+                    format!("unknown verb form `{}`", p[1].text),
+                    "use one of pres, fut, past, inf or imp",
+                )),
+            };
+            Keyword::Verbal(root_from(keyword, &p[0], lang)?, form, subject, polarity, mood)
+        },
+        "vadj" => Keyword::VerbalAdjective(root_from(keyword, &expect(keyword, parameters, &["root"])?[0], lang)?),
+        "prep" => Keyword::Prepositional(root_from(keyword, &expect(keyword, parameters, &["root"])?[0], lang)?),
+        "aadj" => Keyword::AdjectAdjective(root_from(keyword, &expect(keyword, parameters, &["root"])?[0], lang)?),
+
+        "aspComp" => {expect(keyword, parameters, &[])?; Keyword::CompletiveAspect},
+        "aspProg" => {expect(keyword, parameters, &[])?; Keyword::ProgressiveAspect},
+        "aspHabt" => {expect(keyword, parameters, &[])?; Keyword::HabitualAspect},
+        "aspPerf" => {expect(keyword, parameters, &[])?; Keyword::PerfectAspect},
+
+        "artDef" => Keyword::DefiniteArticle(deixis_from_string(&expect(keyword, parameters, &["deixis"])?[0], true)?),
+        "artIndef" => Keyword::IndefiniteArticle(deixis_from_string(&expect(keyword, parameters, &["deixis"])?[0], true)?),
+        "dNounSpac" => Keyword::DeicticSpatialNoun(deixis_from_string(&expect(keyword, parameters, &["deixis"])?[0], false)?),
+        "dNounTemp" => Keyword::DeicticTemporalNoun(deixis_from_string(&expect(keyword, parameters, &["deixis"])?[0], false)?),
+
+        "wh" => {
+            let word = &expect(keyword, parameters, &["word"])?[0];
+            let words = lang.map(|l| l.forms.question_words.keys().cloned().collect::<Vec<String>>());
+            match words {
+                Some(words) if !words.contains(&word.text) => return Err(MarkupError::new(
+                    MarkupErrorKind::InvalidParameter,
+                    word.span,
+                    format!("this language has no question word `{}`", word.text),
+                    if words.is_empty() {"add `question_words` to forms.yaml".to_string()} else {format!("use one of {}", words.join(", "))},
+                )),
+                _ => Keyword::Interrogative(word.text.clone()),
+            }
+        },
+
+        "num" => {
+            if lang.is_some_and(|l| l.forms.numerals.is_none()) {
+                return Err(MarkupError::new(
+                    MarkupErrorKind::UnknownKeyword,
+                    keyword.span,
+                    "this language has no numerals",
+                    "add `numerals` to forms.yaml",
+                ));
             }
-        }),
-        "vadj" => Keyword::VerbalAdjective(parameters[0].clone()),
-        "prep" => Keyword::Prepositional(parameters[0].clone()),
-        "aadj" => Keyword::AdjectAdjective(parameters[0].clone()),
+            let value = &expect(keyword, parameters, &["value"])?[0];
+            let Ok(number) = value.text.parse::<u64>() else {
+                return Err(MarkupError::new(
+                    MarkupErrorKind::InvalidParameter,
+                    value.span,
+                    format!("`{}` is not a whole number", value.text),
+                    "write it in digits, like `num{37}`",
+                ));
+            };
+            match lang.and_then(|l| l.forms.numerals.as_ref()).map(|n| n.max()) {
+                Some(max) if number > max => return Err(MarkupError::new(
+                    MarkupErrorKind::InvalidParameter,
+                    value.span,
+                    format!("{} is too big to say in this language", number),
+                    format!("the biggest number it has words for is {}, add more `powers` to forms.yaml", max),
+                )),
+                _ => Keyword::Numeral(number),
+            }
+        },
+
+        "pron" => {
+            if lang.is_some_and(|l| l.forms.pronouns.is_empty()) {
+                return Err(MarkupError::new(
+                    MarkupErrorKind::UnknownKeyword,
+                    keyword.span,
+                    "this language has no pronouns",
+                    "add a `pronouns` table to forms.yaml",
+                ));
+            }
+            // clusivity and formality are optional and can come in either order
+            let names: &[&str] = match parameters.len() {
+                0..=2 => &["person", "number"],
+                3 => &["person", "number", "clusivity or formality"],
+                _ => &["person", "number", "clusivity", "formality"],
+            };
+            let p = expect(keyword, parameters, names)?;
+            let person = person_from_string(&p[0])?;
+            let number = number_from_string(&p[1])?;
+            let mut clusivity = None;
+            let mut formality = None;
+            for token in &p[2..] {
+                match token.text.as_str() {
+                    "incl" | "excl" if person != Person::First || number == Number::Singular => return Err(MarkupError::new(
+                        MarkupErrorKind::InvalidParameter,
+                        token.span,
+                        "only first person plural pronouns can be inclusive or exclusive",
+                        "write it as `pron{1 pl incl}` or `pron{1 pl excl}`",
+                    )),
+                    "incl" if clusivity.is_none() => clusivity = Some(Clusivity::Inclusive),
+                    "excl" if clusivity.is_none() => clusivity = Some(Clusivity::Exclusive),
+                    "fam" if formality.is_none() => formality = Some(Formality::Familiar),
+                    "form" if formality.is_none() => formality = Some(Formality::Formal),
+                    _ => return Err(MarkupError::new(
+                        MarkupErrorKind::InvalidParameter,
+                        token.span,
+                        format!("unknown or repeated pronoun feature `{}`", token.text),
+                        "use incl or excl for clusivity and fam or form for formality, at most one of each",
+                    )),
+                }
+            }
+            Keyword::Pronoun(person, number, clusivity, formality)
+        },
+
+        name => match lang.and_then(|l| l.forms.keywords.get(name)) {
+            Some(definition) => {
+                let mut names: Vec<&str> = definition.parameters.iter().map(|p| p.name.as_str()).collect();
+                if definition.mold.is_some() {names.insert(0, "root")}
+                let mut p = expect(keyword, parameters, &names)?.iter();
+                let root = match definition.mold {
+                    Some(_) => p.next().map(|t| root_from(keyword, t, lang)).transpose()?,
+                    None => None,
+                };
+
+                let mut values = vec![];
+                for (parameter, token) in definition.parameters.iter().zip(p) {
+                    if !parameter.values.contains_key(&token.text) {
+                        return Err(MarkupError::new(
+                            MarkupErrorKind::InvalidParameter,
+                            token.span,
+                            format!("unknown {} `{}`", parameter.name, token.text),
+                            format!("use one of {}", parameter.values.keys().cloned().collect::<Vec<String>>().join(", ")),
+                        ));
+                    }
+                    values.push(token.text.clone());
+                }
+                Keyword::Defined(name.to_string(), root, values)
+            },
+            None => return Err(MarkupError::new(
+                MarkupErrorKind::UnknownKeyword,
+                keyword.span,
+                format!("unknown keyword `{}`", keyword.text),
+                format!("valid keywords are {}", KEYWORDS.iter().copied()
+                    .chain(lang.iter().flat_map(|l| l.forms.keywords.keys().map(|k| k.as_str())))
+                    .collect::<Vec<&str>>().join(", ")),
+            )),
+        },
+    })
+}
+
+fn person_from_string(person: &Token) -> Result<Person, MarkupError> {
+    match person.text.as_str() {
+        "1" => Ok(Person::First),
+        "2" => Ok(Person::Second),
+        "3" => Ok(Person::Third),
+        _ => Err(MarkupError::new(
+            MarkupErrorKind::InvalidParameter,
+            person.span,
+            format!("unknown person `{}`", person.text),
+            "use 1, 2 or 3",
+        )),
+    }
+}
+
+fn number_from_string(number: &Token) -> Result<Number, MarkupError> {
+    match number.text.as_str() {
+        "sg" => Ok(Number::Singular),
+        "pl" => Ok(Number::Plural),
+        _ => Err(MarkupError::new(
+            MarkupErrorKind::InvalidParameter,
+            number.span,
+            format!("unknown number `{}`", number.text),
+            "use sg or pl",
+        )),
+    }
+}
+
+/// `0` and `1` are short for singular and plural,
+/// dual and paucal need a suffix in forms.yaml when there's a `lang` to check against
+fn noun_number_from_string(token: &Token, lang: Option<&Lang>) -> Result<NounNumber, MarkupError> {
+    let (number, suffix) = match token.text.as_str() {
+        "0" | "sg" => return Ok(NounNumber::Singular),
+        "1" | "pl" => return Ok(NounNumber::Plural),
+        "du" => (NounNumber::Dual, lang.map(|l| &l.forms.noun_dual_suffix)),
+        "pc" => (NounNumber::Paucal, lang.map(|l| &l.forms.noun_paucal_suffix)),
+        _ => return Err(MarkupError::new(
+            MarkupErrorKind::InvalidParameter,
+            token.span,
+            format!("unknown number `{}`", token.text),
+            "use 0 or sg for singular, 1 or pl for plural, du for dual or pc for paucal",
+        )),
+    };
+    if let Some(None) = suffix {
+        return Err(MarkupError::new(
+            MarkupErrorKind::InvalidParameter,
+            token.span,
+            format!("this language has no {}", number.as_name()),
+            format!("add `noun_{}_suffix` to forms.yaml or use pl", number.as_name()),
+        ));
+    }
+    Ok(number)
+}
 
-        "aspComp" => Keyword::CompletiveAspect,
-        "aspProg" => Keyword::ProgressiveAspect,
-        "aspHabt" => Keyword::HabitualAspect,
-        "aspPerf" => Keyword::PerfectAspect,
+/// without a `lang` any case is fine, otherwise it has to be nominative or listed under `noun_cases`
+fn case_from_string(case: &Token, lang: Option<&Lang>) -> Result<Case, MarkupError> {
+    let valid = |lang: Option<&Lang>| -> Vec<&str> {
+        Case::ALL.iter()
+            .filter(|c| lang.is_none_or(|l| **c == Case::Nominative || l.forms.noun_cases.contains_key(c.as_str())))
+            .map(|c| c.as_str())
+            .collect()
+    };
+    match Case::from_name(&case.text) {
+        Some(c) if valid(lang).contains(&c.as_str()) => Ok(c),
+        Some(_) => Err(MarkupError::new(
+            MarkupErrorKind::InvalidParameter,
+            case.span,
+            format!("this language has no `{}` case", case.text),
+            format!("use one of {}", valid(lang).join(", ")),
+        )),
+        None => Err(MarkupError::new(
+            MarkupErrorKind::InvalidParameter,
+            case.span,
+            format!("unknown case `{}`", case.text),
+            format!("use one of {}", valid(lang).join(", ")),
+        )),
+    }
+}
 
-        "artDef" => Keyword::DefiniteArticle(deixis_from_string(parameters[0].clone(), true)),
-        "artIndef" => Keyword::IndefiniteArticle(deixis_from_string(parameters[0].clone(), true)),
-        "dNounSpac" => Keyword::DeicticSpatialNoun(deixis_from_string(parameters[0].clone(), false)),
-        "dNounTemp" => Keyword::DeicticTemporalNoun(deixis_from_string(parameters[0].clone(), false)),
+impl Case {
+    pub const ALL: [Case; 10] = [
+        Case::Nominative, Case::Accusative, Case::Genitive, Case::Dative, Case::Locative,
+        Case::Instrumental, Case::Ablative, Case::Allative, Case::Comitative, Case::Vocative,
+    ];
 
-        
-        _ => panic!("unkown keyword {}", keyword)
+    /// how the case is written in markup and in forms.yaml
+    pub fn as_str(&self) -> &str {
+        match self {
+            Case::Nominative => "nom",
+            Case::Accusative => "acc",
+            Case::Genitive => "gen",
+            Case::Dative => "dat",
+            Case::Locative => "loc",
+            Case::Instrumental => "instr",
+            Case::Ablative => "abl",
+            Case::Allative => "all",
+            Case::Comitative => "com",
+            Case::Vocative => "voc",
+        }
+    }
+
+    pub fn from_name(case: &str) -> Option<Case> {
+        Case::ALL.into_iter().find(|c| c.as_str() == case)
+    }
+
+    pub fn gloss_label(&self) -> &str {
+        match self {
+            Case::Nominative => "NOM",
+            Case::Accusative => "ACC",
+            Case::Genitive => "GEN",
+            Case::Dative => "DAT",
+            Case::Locative => "LOC",
+            Case::Instrumental => "INS",
+            Case::Ablative => "ABL",
+            Case::Allative => "ALL",
+            Case::Comitative => "COM",
+            Case::Vocative => "VOC",
+        }
+    }
+}
+
+impl Person {
+    pub const ALL: [Person; 3] = [Person::First, Person::Second, Person::Third];
+
+    /// as written in markup, also the gloss label
+    pub fn as_str(&self) -> &str {
+        match self {
+            Person::First => "1",
+            Person::Second => "2",
+            Person::Third => "3",
+        }
+    }
+}
+
+impl Number {
+    pub const ALL: [Number; 2] = [Number::Singular, Number::Plural];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Number::Singular => "sg",
+            Number::Plural => "pl",
+        }
+    }
+
+    pub fn gloss_label(&self) -> &str {
+        match self {
+            Number::Singular => "SG",
+            Number::Plural => "PL",
+        }
+    }
+}
+
+impl NounNumber {
+    /// as written in markup
+    pub fn as_str(&self) -> &str {
+        match self {
+            NounNumber::Singular => "sg",
+            NounNumber::Dual => "du",
+            NounNumber::Paucal => "pc",
+            NounNumber::Plural => "pl",
+        }
+    }
+
+    pub fn as_name(&self) -> &str {
+        match self {
+            NounNumber::Singular => "singular",
+            NounNumber::Dual => "dual",
+            NounNumber::Paucal => "paucal",
+            NounNumber::Plural => "plural",
+        }
+    }
+
+    pub fn gloss_label(&self) -> &str {
+        match self {
+            NounNumber::Singular => "SG",
+            NounNumber::Dual => "DU",
+            NounNumber::Paucal => "PC",
+            NounNumber::Plural => "PL",
+        }
+    }
+
+    /// what a verb agrees with, verbs only tell singular from plural
+    pub fn agreement(&self) -> Number {
+        match self {
+            NounNumber::Singular => Number::Singular,
+            _ => Number::Plural,
+        }
+    }
+}
+
+impl Clusivity {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Clusivity::Inclusive => "incl",
+            Clusivity::Exclusive => "excl",
+        }
+    }
+
+    pub fn gloss_label(&self) -> &str {
+        match self {
+            Clusivity::Inclusive => "INCL",
+            Clusivity::Exclusive => "EXCL",
+        }
+    }
+}
+
+impl Formality {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Formality::Familiar => "fam",
+            Formality::Formal => "form",
+        }
+    }
+
+    pub fn gloss_label(&self) -> &str {
+        match self {
+            Formality::Familiar => "FAM",
+            Formality::Formal => "POL",
+        }
     }
 }
 
@@ -91,105 +616,410 @@ impl Deixis {
             Deixis::Distal => "distal",
         }
     }
-}
 
-pub fn to_object(text: &str) -> Vec<Keyword> {
-    const DEBUG_PRINT: bool = false;
+    pub fn gloss_label(&self) -> &str {
+        match self {
+            Deixis::NonSpatial => "NSPAC",
+            Deixis::Proximal => "PROX",
+            Deixis::Immediate => "IMM",
+            Deixis::Distal => "DIST",
+        }
+    }
+}
 
-    let mut cleaned = String::new();
+impl VerbForm {
+    pub const ALL: [VerbForm; 5] = [
+        VerbForm::TensePresent, VerbForm::TenseFuture, VerbForm::TensePast, VerbForm::Infinitive, VerbForm::Imperative,
+    ];
 
-    for line in text.lines() {
-        let no_comment = match line.find(";") {
-            Some(i) => {
-                &line[0..i]
-            },
-            None => line
-        };
+    /// as written in markup
+    pub fn as_str(&self) -> &str {
+        match self {
+            VerbForm::TensePresent => "pres",
+            VerbForm::TenseFuture => "fut",
+            VerbForm::TensePast => "past",
+            VerbForm::Infinitive => "inf",
+            VerbForm::Imperative => "imp",
+        }
+    }
 
-        cleaned.push_str(format!(" {}", no_comment).as_str());
+    pub fn gloss_label(&self) -> &str {
+        match self {
+            VerbForm::TensePresent => "PRS",
+            VerbForm::TenseFuture => "FUT",
+            VerbForm::TensePast => "PST",
+            VerbForm::Infinitive => "INF",
+            VerbForm::Imperative => "IMP",
+        }
     }
+}
 
-    let mut reading_keyword = false;
-    let mut reading_parameters = false;
-    let mut reading_parameter = false;
-    let mut last_char_was_space = false;
+/// parses markup into keywords, reporting every problem it finds rather than stopping at the first
+///
+/// only knows the built in keywords, use `Lang::parse` for the ones a language declares too
+pub fn to_object(text: &str) -> Result<Vec<Keyword>, Vec<MarkupError>> {
+    to_object_with(text, None)
+}
+
+/// like `to_object`, checking the keywords against `lang` when there is one, see `keyword_from_string`
+pub fn to_object_with(text: &str, lang: Option<&Lang>) -> Result<Vec<Keyword>, Vec<MarkupError>> {
+    Ok(to_object_spans(text, lang)?.into_iter().map(|(k, _)| k).collect())
+}
 
-    let mut word = String::new();
-    let mut keyword = String::new();
-    let mut parameters: Vec<String> = vec![];
+/// like `to_object_with`, along with where each keyword was written, from its name to its closing `}`
+pub fn to_object_spans(text: &str, lang: Option<&Lang>) -> Result<Vec<(Keyword, Span)>, Vec<MarkupError>> {
+    to_object_spans_with(text, lang, &BTreeMap::new())
+}
 
-    let mut objects: Vec<Keyword> = vec![];
+/// like `to_object_spans`, filling in `$name` parameters from `variables` first,
+/// a variable it doesn't have being an error like any other
+pub fn to_object_spans_with(text: &str, lang: Option<&Lang>, variables: &dyn Variables) -> Result<Vec<(Keyword, Span)>, Vec<MarkupError>> {
+    enum State {
+        Idle,
+        Keyword,
+        Parameters,
+    }
 
-    const SYMBOL_CHARS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    fn is_symbol_char(c: char) -> bool {
+        c.is_ascii_alphanumeric() || c == '-' || c == '_'
+    }
 
-    for char in cleaned.chars() {
-        // symbol creation
-        if SYMBOL_CHARS.contains(char) {
-            if !reading_keyword {reading_keyword = true; word.clear()}
-            else if reading_parameters && !reading_parameter {reading_parameter = true; word.clear()};
+    // parameters can also be compound and derived roots, like `fire+water>dim`, or variables, like `$target.root`
+    fn is_parameter_char(c: char) -> bool {
+        is_symbol_char(c) || c == '+' || c == '>' || c == '$' || c == '.'
+    }
 
-            word.push(char);
+    // variables are swapped for their values before anything else looks at the parameters
+    let resolve = |parameters: &[Token], errors: &mut Vec<MarkupError>| -> Option<Vec<Token>> {
+        let mut resolved = vec![];
+        let mut found = true;
+        for parameter in parameters {
+            let Some(name) = parameter.text.strip_prefix('$') else {
+                match parameter.text.char_indices().find(|(_, c)| *c == '$' || *c == '.') {
+                    Some((i, c)) => {
+                        let column = parameter.span.column + parameter.text[..i].chars().count();
+                        let start = parameter.span.start + i;
+                        errors.push(unexpected_character(c, Span { start, end: start + c.len_utf8(), line: parameter.span.line, column }));
+                        found = false;
+                    },
+                    None => resolved.push(parameter.clone()),
+                }
+                continue;
+            };
+            match variables.variable(name) {
+                // a value is written into the markup as it is, so it can't bring in braces or more variables
+                Some(value) => match value.split_whitespace().find(|v| !v.chars().all(|c| is_parameter_char(c) && c != '$' && c != '.')) {
+                    Some(bad) => {
+                        errors.push(MarkupError::new(
+                            MarkupErrorKind::InvalidParameter,
+                            parameter.span,
+                            format!("variable `{}` is `{}`, which can't be a parameter", name, bad),
+                            "values may only contain letters, digits, `-` and `_`, roots `+` and `>` as well",
+                        ));
+                        found = false;
+                    },
+                    None => resolved.extend(value.split_whitespace().map(|v| Token { text: v.to_string(), span: parameter.span })),
+                },
+                None => {
+                    errors.push(MarkupError::new(
+                        MarkupErrorKind::UnknownVariable,
+                        parameter.span,
+                        format!("no variable `{}`", name),
+                        "check the name against what the game fills in, or write the parameter out",
+                    ));
+                    found = false;
+                },
+            }
         }
+        found.then_some(resolved)
+    };
 
-        // starting parameter
-        if char == '{' {
-            if reading_keyword {
-                if DEBUG_PRINT {println!("keyword {}", word);};
-                keyword = word.clone();
-                parameters = vec![]; // clear our parameters now
-                reading_parameters = true; word.clear();
-            } else {
-                panic!("invalid syntax: parameters have no keyword body")
-            }
+    let push_keyword = |keyword: &Token, parameters: &[Token], end: usize, objects: &mut Vec<(Keyword, Span)>, errors: &mut Vec<MarkupError>| {
+        let Some(parameters) = resolve(parameters, errors) else {return};
+        match keyword_from_string(keyword, &parameters, lang) {
+            Ok(k) => objects.push((k, Span { end, ..keyword.span })),
+            Err(e) => errors.push(e),
         }
+    };
 
-        // closing parameter
-        if char == '}' {
-            if !reading_keyword || !reading_parameters || !reading_parameter {
-                panic!("invalid syntax, closing bracket on no parameter body")
-            } else {
-                if DEBUG_PRINT {println!("  parameter {}", word);};
-                parameters.push(word.clone());
+    fn extend(token: &mut Option<Token>, c: char, span: Span) {
+        match token {
+            Some(t) => {
+                t.text.push(c);
+                t.span.end = span.end;
+            },
+            None => *token = Some(Token { text: c.to_string(), span }),
+        }
+    }
 
-                // push keyword and parameters
-                if DEBUG_PRINT {println!("pushing, keyword={}, parameters={:?}", keyword, parameters);}
-                objects.push(keyword_from_string(keyword.clone().as_str(), parameters.clone()));
-                parameters = vec![];
+    let mut objects: Vec<(Keyword, Span)> = vec![];
+    let mut errors: Vec<MarkupError> = vec![];
 
-                reading_keyword = false; reading_parameters = false; reading_parameter = false; word.clear();
-            }
+    let mut state = State::Idle;
+    let mut keyword: Option<Token> = None; // none while reading parameters that have no keyword
+    let mut parameter: Option<Token> = None;
+    let mut parameters: Vec<Token> = vec![];
+    let mut open_brace: Option<Span> = None;
+
+    let mut line = 1;
+    let mut column = 1;
+    let mut in_comment = false;
+
+    for (i, c) in text.char_indices() {
+        let span = Span { start: i, end: i + c.len_utf8(), line, column };
+        if c == '\n' {
+            line += 1; column = 1;
+        } else {
+            column += 1;
         }
-        
-        // spaces
-        if char == ' ' && !last_char_was_space {
-            if reading_keyword {
-                if !reading_parameters {
-                    if DEBUG_PRINT {println!("keyword {}", word);}
-
-                    // push with no arguments
-                    if DEBUG_PRINT {println!("pushing, keyword={}, NO PARAMETERS", keyword);}
-                    objects.push(keyword_from_string(word.clone().as_str(), vec![]));
-                    
-                    reading_parameters = false; word.clear()
-                } else {
-                    if reading_parameter {
-                        if DEBUG_PRINT {println!("  parameter {}", word);}
 
-                        // add parameter
-                        parameters.push(word.clone());
+        // comments run from `;` to the end of the line and act like whitespace
+        if c == '\n' {in_comment = false} else if c == ';' {in_comment = true} else if in_comment {continue}
+        let whitespace = c == ';' || c.is_whitespace();
 
-                        reading_parameter = false; word.clear();
-                    }
+        match state {
+            State::Idle => {
+                if whitespace {
+                    continue
+                } else if is_symbol_char(c) {
+                    extend(&mut keyword, c, span);
+                    state = State::Keyword;
+                } else if c == '{' {
+                    errors.push(MarkupError::new(
+                        MarkupErrorKind::ParametersWithoutKeyword,
+                        span,
+                        "parameters have no keyword",
+                        "parameters go directly after a keyword with no space, e.g. `nom{water 0}`",
+                    ));
+                    // read the parameters anyway so they aren't mistaken for keywords
+                    open_brace = Some(span);
+                    state = State::Parameters;
+                } else if c == '}' {
+                    errors.push(MarkupError::new(
+                        MarkupErrorKind::UnmatchedClosingBrace,
+                        span,
+                        "closing bracket with no parameters open",
+                        "remove the `}` or add the matching `{`",
+                    ));
+                } else {
+                    errors.push(unexpected_character(c, span));
                 }
+            },
+            State::Keyword => {
+                if whitespace {
+                    if let Some(k) = keyword.take() {push_keyword(&k, &[], k.span.end, &mut objects, &mut errors)}
+                    state = State::Idle;
+                } else if is_symbol_char(c) {
+                    extend(&mut keyword, c, span);
+                } else if c == '{' {
+                    open_brace = Some(span);
+                    state = State::Parameters;
+                } else if c == '}' {
+                    errors.push(MarkupError::new(
+                        MarkupErrorKind::UnmatchedClosingBrace,
+                        span,
+                        "closing bracket with no parameters open",
+                        "remove the `}` or add the matching `{`",
+                    ));
+                    if let Some(k) = keyword.take() {push_keyword(&k, &[], k.span.end, &mut objects, &mut errors)}
+                    state = State::Idle;
+                } else {
+                    errors.push(unexpected_character(c, span));
+                }
+            },
+            State::Parameters => {
+                if whitespace {
+                    if let Some(p) = parameter.take() {parameters.push(p)}
+                } else if is_parameter_char(c) {
+                    extend(&mut parameter, c, span);
+                } else if c == '}' {
+                    if let Some(p) = parameter.take() {parameters.push(p)}
+                    if let Some(k) = keyword.take() {push_keyword(&k, &parameters, span.end, &mut objects, &mut errors)}
+                    parameters.clear();
+                    open_brace = None;
+                    state = State::Idle;
+                } else if c == '{' {
+                    errors.push(MarkupError::new(
+                        MarkupErrorKind::NestedBrace,
+                        span,
+                        "parameters cannot contain another `{`",
+                        "close the previous parameters with `}` first",
+                    ));
+                } else {
+                    errors.push(unexpected_character(c, span));
+                }
+            },
+        }
+    }
+
+    match state {
+        State::Idle => (),
+        State::Keyword => {
+            if let Some(k) = keyword.take() {push_keyword(&k, &[], k.span.end, &mut objects, &mut errors)}
+        },
+        State::Parameters => {
+            if let Some(brace) = open_brace {
+                errors.push(MarkupError::new(
+                    MarkupErrorKind::UnclosedBrace,
+                    brace,
+                    "parameters are never closed",
+                    "add a `}` after the last parameter",
+                ));
             }
+        },
+    }
+
+    if errors.is_empty() {
+        Ok(objects)
+    } else {
+        Err(errors)
+    }
+}
 
-            last_char_was_space = true
-        } else if char == ' ' {
-            last_char_was_space = true
+fn unexpected_character(c: char, span: Span) -> MarkupError {
+    MarkupError::new(
+        MarkupErrorKind::UnexpectedCharacter,
+        span,
+        format!("unexpected character `{}`", c),
+        if c == ',' {
+            "parameters are separated by spaces, not commas"
         } else {
-            last_char_was_space = false
+            "keywords and parameters may only contain letters, digits, `-` and `_`, roots `+` and `>` as well, \
+                and a parameter starting with `$` is a variable"
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<MarkupErrorKind> {
+        to_object(text).unwrap_err().into_iter().map(|e| e.kind).collect()
+    }
+
+    #[test]
+    fn only_first_person_plurals_take_clusivity() {
+        assert_eq!(
+            to_object("pron{1 pl excl} pron{2 sg form}").unwrap(),
+            vec![
+                Keyword::Pronoun(Person::First, Number::Plural, Some(Clusivity::Exclusive), None),
+                Keyword::Pronoun(Person::Second, Number::Singular, None, Some(Formality::Formal)),
+            ],
+        );
+        assert_eq!(to_object("pron{1 pl incl fam}").unwrap(), vec![Keyword::Pronoun(Person::First, Number::Plural, Some(Clusivity::Inclusive), Some(Formality::Familiar))]);
+        for markup in ["pron{2 pl incl}", "pron{1 sg excl}", "pron{1 pl incl excl}", "pron{2 sg fam form}", "pron{3 sg polite}"] {
+            assert_eq!(kinds(markup), vec![MarkupErrorKind::InvalidParameter], "{}", markup);
         }
-    };
+        let error = to_object("pron{1 sg incl}").unwrap_err().remove(0);
+        assert_eq!((error.span.start, error.span.end), (10, 14));
+    }
+
+    #[test]
+    fn reports_every_error_in_a_line() {
+        assert_eq!(
+            kinds("nom{water} bogus artDef{far} verb{run, pres}"),
+            vec![
+                MarkupErrorKind::MissingParameter,
+                MarkupErrorKind::UnknownKeyword,
+                MarkupErrorKind::InvalidDeixis,
+                MarkupErrorKind::UnexpectedCharacter,
+            ],
+        );
+    }
+
+    #[test]
+    fn reports_unclosed_and_unmatched_braces() {
+        assert_eq!(kinds("nom{water 0"), vec![MarkupErrorKind::UnclosedBrace]);
+        assert_eq!(kinds("nom{water 0}}"), vec![MarkupErrorKind::UnmatchedClosingBrace]);
+        assert_eq!(kinds("{water 0}"), vec![MarkupErrorKind::ParametersWithoutKeyword]);
+        assert_eq!(kinds("nom{water {0}"), vec![MarkupErrorKind::NestedBrace]);
+    }
+
+    #[test]
+    fn reports_unknown_keywords_and_missing_parameters() {
+        let errors = to_object("aspProg nomm{water 0}").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, MarkupErrorKind::UnknownKeyword);
+        assert!(errors[0].message.contains("nomm"));
+
+        let errors = to_object("verb{run}").unwrap_err();
+        assert_eq!(errors[0].kind, MarkupErrorKind::MissingParameter);
+        assert!(errors[0].message.contains("verb"));
+    }
+
+    #[test]
+    fn spans_point_at_the_offending_text() {
+        let text = "nom{water 0}\n  artDef{far}";
+        let errors = to_object(text).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        let span = errors[0].span;
+        assert_eq!((span.line, span.column), (2, 10));
+        assert_eq!(&text[span.start..span.end], "far");
+
+        // columns count characters, not bytes
+        let errors = to_object("nom{ä 0} ,").unwrap_err();
+        let comma = errors.iter().find(|e| e.message.contains("`,`")).unwrap();
+        assert_eq!((comma.span.column, comma.span.start), (10, 10));
+    }
+
+    #[test]
+    fn keyword_spans_run_to_the_closing_brace() {
+        let text = "aspProg verb{run pres}";
+        let spans: Vec<&str> = to_object_spans(text, None).unwrap().into_iter().map(|(_, s)| &text[s.start..s.end]).collect();
+        assert_eq!(spans, vec!["aspProg", "verb{run pres}"]);
+    }
 
-    objects
-}
\ No newline at end of file
+    fn with(text: &str, variables: &dyn Variables) -> Result<Vec<Keyword>, Vec<MarkupError>> {
+        Ok(to_object_spans_with(text, None, variables)?.into_iter().map(|(k, _)| k).collect())
+    }
+
+    #[test]
+    fn fills_in_variables() {
+        let water = |number| Keyword::Nominative("water".to_string(), number, Case::Nominative);
+        assert_eq!(with("nom{$item 0}", &[("item", "water")]), Ok(vec![water(NounNumber::Singular)]));
+        assert_eq!(with("nom{$target.root 1}", &[("target.root", "water")]), Ok(vec![water(NounNumber::Plural)]));
+        // a value can be several parameters
+        assert_eq!(with("nom{$item}", &[("item", "water 1")]), Ok(vec![water(NounNumber::Plural)]));
+        let map: std::collections::HashMap<String, String> = [("item".to_string(), "water".to_string())].into();
+        assert_eq!(with("nom{$item 0}", &map), Ok(vec![water(NounNumber::Singular)]));
+
+        let lang = Lang::load("assets/lang").unwrap();
+        assert_eq!(lang.render_with("nom{$item 0}", &[("item", "water")]).unwrap().trim(), "wida");
+    }
+
+    #[test]
+    fn unknown_variables_point_at_themselves() {
+        let text = "nom{$item 0} verb{$verb pres} bogus";
+        let errors = with(text, &[("verb", "run")]).unwrap_err();
+        assert_eq!(errors.iter().map(|e| e.kind).collect::<Vec<_>>(), vec![MarkupErrorKind::UnknownVariable, MarkupErrorKind::UnknownKeyword]);
+        assert_eq!(&text[errors[0].span.start..errors[0].span.end], "$item");
+        assert_eq!(errors[0].span.column, 5);
+        assert!(errors[0].message.contains("`item`"));
+
+        // a value that doesn't fit is blamed on the variable too
+        let errors = with("nom{$item}", &[("item", "water lots")]).unwrap_err();
+        assert_eq!((errors[0].kind, errors[0].span.start, errors[0].span.end), (MarkupErrorKind::InvalidParameter, 4, 9));
+    }
+
+    #[test]
+    fn variable_values_are_checked_like_parameters() {
+        let text = "nom{water 0} nom{$item 0}";
+        for value in ["wa}ter", "$other", "water.root", "water 0,"] {
+            let errors = with(text, &[("item", value)]).unwrap_err();
+            assert_eq!(errors.iter().map(|e| e.kind).collect::<Vec<_>>(), vec![MarkupErrorKind::InvalidParameter], "{}", value);
+            assert_eq!(&text[errors[0].span.start..errors[0].span.end], "$item", "{}", value);
+        }
+        assert!(with(text, &[("item", "fire+water>dim")]).is_ok());
+    }
+
+    #[test]
+    fn variable_characters_only_go_at_the_start() {
+        let text = "nom{wa$ter 0} nom{wa.ter 0}";
+        let errors = to_object(text).unwrap_err();
+        assert_eq!(errors.iter().map(|e| e.kind).collect::<Vec<_>>(), vec![MarkupErrorKind::UnexpectedCharacter; 2]);
+        assert_eq!(&text[errors[0].span.start..errors[0].span.end], "$");
+        assert_eq!(errors[0].span.column, 7);
+        assert_eq!(&text[errors[1].span.start..errors[1].span.end], ".");
+    }
+}
@@ -0,0 +1,405 @@
+use super::*;
+use super::english::*;
+
+/// how a verb in a chain like "will have been running" is inflected
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VerbShape {
+    /// agreeing with the subject and carrying the tense
+    Finite,
+    Base,
+    /// "running"
+    Ing,
+    /// "run" as in "have run"
+    Participle,
+}
+
+/// the english preposition standing in for a case, none for the ones english shows by word order
+fn case_preposition(case: &Case) -> Option<&'static str> {
+    match case {
+        Case::Nominative | Case::Accusative | Case::Vocative => None,
+        Case::Genitive => Some("of"),
+        Case::Dative => Some("to"),
+        Case::Locative => Some("at"),
+        Case::Instrumental => Some("with"),
+        Case::Ablative => Some("from"),
+        Case::Allative => Some("toward"),
+        Case::Comitative => Some("along with"),
+    }
+}
+
+/// "they" is used for a singular third person, as nothing says whether it's a he, a she or an it
+fn pronoun(person: &Person, number: &Number, object: bool) -> &'static str {
+    match (person, number, object) {
+        (Person::First, Number::Singular, false) => "I",
+        (Person::First, Number::Singular, true) => "me",
+        (Person::First, Number::Plural, false) => "we",
+        (Person::First, Number::Plural, true) => "us",
+        (Person::Second, _, _) => "you",
+        (Person::Third, _, false) => "they",
+        (Person::Third, _, true) => "them",
+    }
+}
+
+/// who a verb agrees with in english, a singular "they" takes a plural verb
+fn subject_of(keyword: &Keyword) -> Option<(Person, Number)> {
+    match keyword.as_subject()? {
+        (Person::Third, _) if matches!(keyword, Keyword::Pronoun(_, _, _, _)) => Some((Person::Third, Number::Plural)),
+        subject => Some(subject),
+    }
+}
+
+/// a verb agreeing with `subject`, in the past tense or not
+fn finite(verb: &str, past_tense: bool, subject: &(Person, Number)) -> String {
+    let singular = subject.1 == Number::Singular;
+    match verb {
+        "will" => verb.to_string(),
+        "be" if past_tense => if singular && subject.0 != Person::Second {"was"} else {"were"}.to_string(),
+        "be" if singular && subject.0 == Person::First => "am".to_string(),
+        "be" if !singular || subject.0 == Person::Second => "are".to_string(),
+        _ if past_tense => past(verb),
+        _ if singular && subject.0 == Person::Third => third_person(verb),
+        _ => verb.to_string(),
+    }
+}
+
+/// the english for a derived word, going by the gloss of the derivation
+fn derived(word: &str, gloss: &str, from: &str) -> String {
+    match (gloss.to_uppercase().as_str(), from) {
+        ("AGT", _) => agent_noun(word),
+        ("ABST", "verb") => present_participle(word),
+        ("ABST", _) => format!("{}ness", word),
+        ("CAUS", _) => format!("make {}", word),
+        ("DIM", _) => format!("little {}", word),
+        _ => format!("{}-{}", word, gloss.to_lowercase()),
+    }
+}
+
+impl SentenceWord {
+    /// the english for the word on its own, see `Lang::english_word`
+    pub fn english(&self, lang: &Lang) -> String {
+        match self.particle.as_deref() {
+            Some("NEG") => "not".to_string(),
+            Some("Q") => "(question)".to_string(),
+            Some(marker) => marker.to_lowercase(),
+            // a negation particle is a word of its own with its own english, the verb's is without it
+            None => match &self.keyword {
+                Keyword::Verbal(root, form, subject, Polarity::Negative, mood)
+                    if lang.forms.negation.as_ref().is_some_and(|m| matches!(m.placement, MarkerPlacement::Before | MarkerPlacement::After)) =>
+                {
+                    lang.english_word(&Keyword::Verbal(root.clone(), form.clone(), subject.clone(), Polarity::Positive, mood.clone()))
+                },
+                keyword => lang.english_word(keyword),
+            },
+        }
+    }
+}
+
+impl Lang {
+    /// markup as a readable english sentence, using the glosses of its roots,
+    /// e.g. "artDef{dist} nom{cat 1} aspProg verb{sleep pres}" as "Those cats are sleeping."
+    pub fn translate_to_english(&self, markup: &str) -> Result<String, Vec<MarkupError>> {
+        Ok(self.english_sentence(&self.parse(markup)?))
+    }
+
+    /// keywords as an english sentence, see `Lang::translate_to_english`
+    ///
+    /// the keywords are taken to be in english order already, like markup is written in,
+    /// only adjectives are moved before their noun and auxiliaries before the subject of a question
+    pub fn english_sentence(&self, keywords: &[Keyword]) -> String {
+        let mut words: Vec<String> = vec![];
+        // where the noun phrase that a following verb agrees with starts in `words`, and who it is
+        let mut subject: Option<(usize, (Person, Number))> = None;
+        let mut asking = false;
+        let mut after_verb = false;
+        let mut ending = ".";
+
+        for phrase in group_phrases(keywords) {
+            let start = words.len();
+            let phrase_keywords = &keywords[phrase.keywords.clone()];
+            match phrase.kind {
+                PhraseKind::Noun => {
+                    words.extend(self.english_noun_phrase(phrase_keywords, after_verb));
+                    if let Some((person, mut number)) = phrase.head.and_then(|h| subject_of(&keywords[h])) {
+                        // "twenty-one cats" take a plural verb whatever number the noun itself is in
+                        if phrase_keywords.iter().any(|k| matches!(k, Keyword::Numeral(n) if *n != 1)) {
+                            number = Number::Plural;
+                        }
+                        subject = Some((start, (person, number)));
+                    }
+                },
+                PhraseKind::Prepositional => {
+                    if let Keyword::Prepositional(root) = &phrase_keywords[0] {
+                        words.push(self.english_root(root, "prep"));
+                    }
+                    words.extend(self.english_noun_phrase(&phrase_keywords[1..], true));
+                },
+                PhraseKind::Verb => {
+                    let Some(Keyword::Verbal(root, form, agreement, polarity, mood)) = phrase.head.map(|h| &keywords[h]) else {continue};
+                    // a verb agreeing with a subject that isn't there gets a pronoun for it
+                    if subject.is_none() && let Some((person, number)) = agreement {
+                        words.push(pronoun(person, number, false).to_string());
+                        subject = subject_of(&Keyword::Pronoun(person.clone(), number.clone(), None, None)).map(|s| (start, s));
+                    }
+                    let question = *mood == Mood::Question || asking;
+                    let invert = question && subject.is_some();
+                    let agrees_with = subject.as_ref().map_or((Person::Third, Number::Singular), |(_, s)| s.clone());
+                    let mut verb = self.english_verb(root, form, phrase_keywords, *polarity == Polarity::Negative, invert, &agrees_with);
+                    if invert && let Some((at, _)) = subject {
+                        words.insert(at, verb.remove(0));
+                    }
+                    words.extend(verb);
+                    if question {
+                        ending = "?";
+                    } else if *form == VerbForm::Imperative {
+                        ending = "!";
+                    }
+                    subject = None;
+                    after_verb = true;
+                },
+                PhraseKind::Other => match &phrase_keywords[0] {
+                    Keyword::Interrogative(word) => {
+                        words.push(word.clone());
+                        asking = true;
+                        ending = "?";
+                    },
+                    Keyword::Defined(name, Some(root), _) => words.push(self.english_root(root, name)),
+                    _ => {},
+                },
+            }
+        }
+
+        let mut sentence = words.join(" ");
+        if let Some(first) = sentence.get(..1) {
+            sentence = format!("{}{}", first.to_uppercase(), &sentence[1..]);
+        }
+        sentence + ending
+    }
+
+    /// the english for one keyword on its own, like "at the fire" or "will run",
+    /// for showing in place of a word rather than as part of a sentence
+    pub fn english_word(&self, keyword: &Keyword) -> String {
+        match keyword {
+            Keyword::Nominative(_, _, _) | Keyword::Adjective(_) | Keyword::VerbalAdjective(_) | Keyword::AdjectAdjective(_)
+                | Keyword::DefiniteArticle(_) | Keyword::IndefiniteArticle(_) | Keyword::DeicticSpatialNoun(_) | Keyword::DeicticTemporalNoun(_)
+                | Keyword::Numeral(_) => {
+                self.english_noun_phrase(std::slice::from_ref(keyword), false).join(" ")
+            },
+            Keyword::Pronoun(person, number, _, _) => pronoun(person, number, false).to_string(),
+            Keyword::Verbal(root, form, agreement, polarity, _) => {
+                let subject = agreement.as_ref()
+                    .and_then(|(p, n)| subject_of(&Keyword::Pronoun(p.clone(), n.clone(), None, None)))
+                    .unwrap_or((Person::Third, Number::Singular));
+                self.english_verb(root, form, &[], *polarity == Polarity::Negative, false, &subject).join(" ")
+            },
+            Keyword::Prepositional(root) => self.english_root(root, "prep"),
+            Keyword::CompletiveAspect => "finished".to_string(),
+            Keyword::ProgressiveAspect => "ongoing".to_string(),
+            Keyword::HabitualAspect => "usually".to_string(),
+            Keyword::PerfectAspect => "already".to_string(),
+            Keyword::Interrogative(word) => word.clone(),
+            Keyword::Defined(name, Some(root), _) => self.english_root(root, name),
+            Keyword::Defined(name, None, _) => self.forms.keywords.get(name).map_or(name.clone(), |d| d.gloss.to_lowercase()),
+        }
+    }
+
+    /// the english for a root written in markup, like `fire+water>dim`, used as `keyword`
+    pub(super) fn english_root(&self, text: &str, keyword: &str) -> String {
+        let spec = RootSpec::parse(text);
+        let Some((head, modifiers)) = spec.roots.split_last() else {return text.to_string()};
+        let Some(root) = self.roots.get(*head) else {return text.to_string()};
+
+        // the head is what the first derivation takes it as, like a verb for an agent noun
+        let derivations: Vec<&Derivation> = spec.derivations.iter().filter_map(|d| self.forms.derivations.get(*d)).collect();
+        let from = derivations.first()
+            .and_then(|d| d.from.iter().find(|k| root.allows(k)))
+            .map_or(keyword, |k| k.as_str());
+        let mut word = root.english_as(head, from);
+        for derivation in derivations {
+            word = derived(&word, &derivation.gloss, from);
+        }
+
+        // compounds are written modifier first like english ones
+        let mut parts: Vec<String> = modifiers.iter()
+            .map(|m| self.roots.get(*m).map_or(m.to_string(), |r| r.english_as(m, "nom")))
+            .collect();
+        parts.push(word);
+        parts.join("-")
+    }
+
+    /// articles, numbers, adjectives and a noun or pronoun, in english order, after the preposition for the noun's case
+    fn english_noun_phrase(&self, keywords: &[Keyword], object: bool) -> Vec<String> {
+        let head = keywords.iter().find(|k| matches!(k.category(), "noun" | "pronoun"));
+        let mut words = vec![];
+        match head {
+            Some(Keyword::Pronoun(person, number, _, _)) => {
+                words.push(pronoun(person, number, object).to_string());
+                return words;
+            },
+            Some(Keyword::DeicticSpatialNoun(deixis)) | Some(Keyword::DeicticTemporalNoun(deixis)) => {
+                let spatial = matches!(head, Some(Keyword::DeicticSpatialNoun(_)));
+                words.push(match (deixis, spatial) {
+                    (Deixis::Distal, true) => "there",
+                    (Deixis::Distal, false) => "then",
+                    (Deixis::Immediate, true) => "right here",
+                    (Deixis::Immediate, false) => "right now",
+                    (_, true) => "here",
+                    (_, false) => "now",
+                }.to_string());
+                return words;
+            },
+            Some(Keyword::Nominative(_, _, case)) => words.extend(case_preposition(case).map(String::from)),
+            _ => {},
+        }
+
+        let number = match head {
+            Some(Keyword::Nominative(_, number, _)) => number.clone(),
+            _ => NounNumber::Singular,
+        };
+        let count = keywords.iter().find_map(|k| if let Keyword::Numeral(n) = k {Some(*n)} else {None});
+        let plural = match count {
+            Some(n) => n != 1,
+            None => number != NounNumber::Singular,
+        };
+        let count = match (count, &number) {
+            (Some(n), _) => Some(number_words(n)),
+            (None, NounNumber::Dual) => Some("two".to_string()),
+            (None, NounNumber::Paucal) => Some("few".to_string()),
+            _ => None,
+        };
+
+        let mut modifiers: Vec<String> = keywords.iter()
+            .filter_map(|k| match k {
+                Keyword::Adjective(root) => Some(self.english_root(root, "adj")),
+                Keyword::VerbalAdjective(root) => Some(present_participle(&self.english_root(root, "verb"))),
+                Keyword::AdjectAdjective(root) => Some(adverb(&self.english_root(root, "aadj"))),
+                _ => None,
+            })
+            .collect();
+        let noun = match head {
+            Some(Keyword::Nominative(root, _, _)) if plural => Some(plural_noun(&self.english_root(root, "nom"))),
+            Some(Keyword::Nominative(root, _, _)) => Some(self.english_root(root, "nom")),
+            _ => None,
+        };
+
+        // "this" and "that" for definite articles, a word like "nearby" for indefinite ones
+        let mut here = false;
+        let article = match keywords.iter().find(|k| k.category() == "article") {
+            Some(Keyword::DefiniteArticle(deixis)) => Some(match deixis {
+                Deixis::NonSpatial => "the",
+                Deixis::Proximal | Deixis::Immediate => {
+                    here = *deixis == Deixis::Immediate;
+                    if plural {"these"} else {"this"}
+                },
+                Deixis::Distal => if plural {"those"} else {"that"},
+            }),
+            Some(Keyword::IndefiniteArticle(deixis)) => {
+                match deixis {
+                    Deixis::Proximal | Deixis::Immediate => modifiers.insert(0, "nearby".to_string()),
+                    Deixis::Distal => modifiers.insert(0, "distant".to_string()),
+                    Deixis::NonSpatial => {},
+                }
+                match &count {
+                    Some(_) if number == NounNumber::Paucal => Some("a"),
+                    Some(_) => None,
+                    None if plural => Some("some"),
+                    None => {
+                        let next = modifiers.first().or(noun.as_ref()).map_or("", |w| w.as_str());
+                        Some(indefinite_article(next))
+                    },
+                }
+            },
+            _ if count.is_some() && number == NounNumber::Paucal => Some("a"),
+            _ => None,
+        };
+
+        words.extend(article.map(String::from));
+        words.extend(count);
+        words.extend(modifiers);
+        words.extend(noun);
+        if here {
+            words.push("here".to_string());
+        }
+        words
+    }
+
+    /// a verb with the auxiliaries its tense, aspects and polarity take in english, like "has not been running",
+    /// `phrase` being the aspect particles and the verb; the first word can go before the subject when `invert`ing for a question
+    fn english_verb(&self, root: &str, form: &VerbForm, phrase: &[Keyword], negative: bool, invert: bool, subject: &(Person, Number)) -> Vec<String> {
+        let has = |aspect: Keyword| phrase.contains(&aspect);
+        let mut chain = vec![(self.english_root(root, "verb"), VerbShape::Finite)];
+        // innermost first, "have been running" rather than "be having run"
+        for (aspect, auxiliary, shape) in [
+            (Keyword::ProgressiveAspect, "be", VerbShape::Ing),
+            (Keyword::CompletiveAspect, "finish", VerbShape::Ing),
+            (Keyword::PerfectAspect, "have", VerbShape::Participle),
+        ] {
+            if has(aspect) {
+                chain[0].1 = shape;
+                chain.insert(0, (auxiliary.to_string(), VerbShape::Finite));
+            }
+        }
+        match form {
+            VerbForm::TenseFuture => {
+                chain[0].1 = VerbShape::Base;
+                chain.insert(0, ("will".to_string(), VerbShape::Finite));
+            },
+            VerbForm::Infinitive | VerbForm::Imperative => chain[0].1 = VerbShape::Base,
+            _ => {},
+        }
+
+        // "do" for negating and asking with a verb that isn't an auxiliary itself
+        let auxiliary = chain[0].0.split(' ').next().is_some_and(|w| ["be", "have", "will"].contains(&w));
+        if *form == VerbForm::Imperative && negative {
+            chain.insert(0, ("do".to_string(), VerbShape::Base));
+        } else if chain[0].1 == VerbShape::Finite && (negative || invert) && !auxiliary {
+            chain[0].1 = VerbShape::Base;
+            chain.insert(0, ("do".to_string(), VerbShape::Finite));
+        }
+
+        let past_tense = *form == VerbForm::TensePast;
+        let mut words: Vec<String> = chain.iter()
+            .map(|(verb, shape)| match shape {
+                VerbShape::Finite => finite(verb, past_tense, subject),
+                VerbShape::Base => verb.clone(),
+                VerbShape::Ing => present_participle(verb),
+                VerbShape::Participle => past_participle(verb),
+            })
+            .collect();
+
+        // "usually" goes after an auxiliary and before anything else
+        if has(Keyword::HabitualAspect) {
+            let after_auxiliary = chain.len() > 1 || auxiliary;
+            words.insert(if after_auxiliary {1} else {0}, "usually".to_string());
+        }
+        match form {
+            VerbForm::Infinitive => {
+                words.insert(0, "to".to_string());
+                if negative {words.insert(0, "not".to_string())}
+            },
+            _ if negative => words.insert(1, "not".to_string()),
+            _ => {},
+        }
+        words
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translates_whole_sentences() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let english = |markup: &str| lang.translate_to_english(markup).unwrap();
+        assert_eq!(
+            english("artIndef{prox} nom{young 1} aspPerf verb{run past} prep{face} artDef{dist} nom{water 0} adj{run}"),
+            "Some nearby children had run toward that running water.",
+        );
+        assert_eq!(
+            english("artDef{nspac} nom{cat 0} aspProg verb{sleep pres} artDef{nspac} nom{fire 0 loc}"),
+            "The cat is sleeping at the fire.",
+        );
+        assert_eq!(english("pron{3 pl} verb{be past}"), "They were.");
+        assert!(lang.translate_to_english("nom{nosuchroot 0}").is_err());
+    }
+}
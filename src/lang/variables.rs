@@ -0,0 +1,34 @@
+use std::collections::{BTreeMap, HashMap};
+
+/// where `$name` parameters in markup get their values from, like the state of the game,
+/// so `nom{$item 0}` can name whatever the player is holding
+///
+/// a value can be more than one parameter, `nom{$item}` with `item` being `water 0` is `nom{water 0}`
+pub trait Variables {
+    /// the value of a variable, names being dotted like `target.root`, `None` if there's no such variable
+    fn variable(&self, name: &str) -> Option<String>;
+}
+
+impl Variables for BTreeMap<String, String> {
+    fn variable(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+}
+
+impl Variables for HashMap<String, String> {
+    fn variable(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+}
+
+impl Variables for [(&str, &str)] {
+    fn variable(&self, name: &str) -> Option<String> {
+        self.iter().find(|(n, _)| *n == name).map(|(_, v)| v.to_string())
+    }
+}
+
+impl<const N: usize> Variables for [(&str, &str); N] {
+    fn variable(&self, name: &str) -> Option<String> {
+        self[..].variable(name)
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// how well the player knows a root or a grammatical marker, in the order they come to know it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Knowledge {
+    #[default]
+    Unseen,
+    /// shown in a sentence at least once
+    Seen,
+    /// the player has put forward a meaning for it, right or not
+    Guessed,
+    Learned,
+}
+
+/// what the player knows of one root or marker
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Known {
+    pub knowledge: Knowledge,
+    /// how many times it's been in a sentence shown to the player
+    pub seen: usize,
+}
+
+/// what the player knows of the language, roots by their key in roots.yaml
+/// and grammatical markers by their gloss label, like `PL` or `DEF.DIST`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Vocabulary {
+    #[serde(default)]
+    pub roots: BTreeMap<String, Known>,
+    #[serde(default)]
+    pub markers: BTreeMap<String, Known>,
+}
+
+/// how much of a word the player can make out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reveal {
+    /// left in the language, nothing in it has been learned
+    Hidden,
+    /// in the language with what's been learned of it after it, like `kadaroni (run-?)`
+    Partial,
+    /// in english, all of it has been learned
+    Shown,
+}
+
+/// one word of a sentence as the player gets to see it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RevealedWord {
+    pub text: String,
+    pub reveal: Reveal,
+}
+
+/// the keyword a root is used with, for picking which of its english words to show
+fn root_keyword(keyword: &Keyword) -> &str {
+    match keyword {
+        Keyword::Nominative(_, _, _) => "nom",
+        Keyword::Verbal(_, _, _, _, _) => "verb",
+        Keyword::VerbalAdjective(_) => "vadj",
+        Keyword::Prepositional(_) => "prep",
+        Keyword::AdjectAdjective(_) => "aadj",
+        Keyword::Defined(name, _, _) => name,
+        _ => "adj",
+    }
+}
+
+impl Keyword {
+    /// the roots it's built on, like `fire` and `water` for `nom{fire+water>dim 0}`
+    pub fn roots(&self) -> Vec<&str> {
+        self.root().map_or(vec![], |root| RootSpec::parse(root).roots)
+    }
+
+    /// the grammatical markers on its word, by their gloss labels like `PL` or `DEF.DIST`, derivations included,
+    /// see `Keyword::particles` for the ones that are words of their own
+    pub fn markers(&self, lang: &Lang) -> Vec<String> {
+        let forms = &lang.forms;
+        // keywords without a root are nothing but markers, and glossed as such
+        let Some(root) = self.root() else {
+            return self.morphemes(lang).unwrap_or_default().into_iter().map(|m| m.gloss).collect();
+        };
+
+        let mut markers: Vec<String> = RootSpec::parse(root).derivations.iter()
+            .filter_map(|d| forms.derivations.get(*d))
+            .map(|d| d.gloss.clone())
+            .collect();
+        let affix = |marker: &Option<Marker>| marker.as_ref().is_some_and(|m| matches!(m.placement, MarkerPlacement::Prefix | MarkerPlacement::Suffix));
+        match self {
+            Keyword::Nominative(_, number, case) => {
+                if *number != NounNumber::Singular {markers.push(number.gloss_label().to_string())}
+                if *case != Case::Nominative {markers.push(case.gloss_label().to_string())}
+            },
+            Keyword::Verbal(_, form, subject, polarity, mood) => {
+                markers.push(form.gloss_label().to_string());
+                if let Some((person, number)) = subject {markers.push(format!("{}{}", person.as_str(), number.gloss_label()))}
+                if *polarity == Polarity::Negative && affix(&forms.negation) {markers.push("NEG".to_string())}
+                if *mood == Mood::Question && affix(&forms.question) {markers.push("Q".to_string())}
+            },
+            Keyword::Defined(name, _, values) => {
+                let parameters = forms.keywords.get(name).map(|d| d.parameters.as_slice()).unwrap_or_default();
+                for (parameter, value) in parameters.iter().zip(values) {
+                    markers.extend(parameter.values.get(value).map(|a| a.gloss.clone()).filter(|g| !g.is_empty()));
+                }
+            },
+            _ => {},
+        }
+        markers
+    }
+
+    /// the root as written in markup, like `fire+water>dim`
+    fn root(&self) -> Option<&str> {
+        match self {
+            Keyword::Adjective(root) | Keyword::Nominative(root, _, _) | Keyword::Verbal(root, _, _, _, _)
+                | Keyword::VerbalAdjective(root) | Keyword::Prepositional(root) | Keyword::AdjectAdjective(root)
+                | Keyword::Defined(_, Some(root), _) => Some(root),
+            _ => None,
+        }
+    }
+}
+
+impl Vocabulary {
+    pub fn new() -> Vocabulary {
+        Vocabulary::default()
+    }
+
+    pub fn root(&self, key: &str) -> Knowledge {
+        self.roots.get(key).map_or(Knowledge::Unseen, |k| k.knowledge)
+    }
+
+    pub fn marker(&self, label: &str) -> Knowledge {
+        self.markers.get(label).map_or(Knowledge::Unseen, |k| k.knowledge)
+    }
+
+    /// raises what the player knows of a root, a guess never takes back something learned
+    pub fn set_root(&mut self, key: &str, knowledge: Knowledge) {
+        let known = self.roots.entry(key.to_string()).or_default();
+        known.knowledge = known.knowledge.max(knowledge);
+    }
+
+    /// like `Vocabulary::set_root`, for a marker
+    pub fn set_marker(&mut self, label: &str, knowledge: Knowledge) {
+        let known = self.markers.entry(label.to_string()).or_default();
+        known.knowledge = known.knowledge.max(knowledge);
+    }
+
+    /// counts every root and marker in a sentence as seen once more, to be called whenever one is shown to the player
+    pub fn show(&mut self, keywords: &[Keyword], lang: &Lang) {
+        let see = |known: &mut Known| {
+            known.seen += 1;
+            known.knowledge = known.knowledge.max(Knowledge::Seen);
+        };
+        for keyword in with_agreement(keywords) {
+            for root in keyword.roots() {
+                see(self.roots.entry(root.to_string()).or_default());
+            }
+            let (before, after) = keyword.particles(lang);
+            let particles = before.into_iter().chain(after).flatten().map(|m| m.gloss);
+            for marker in keyword.markers(lang).into_iter().chain(particles) {
+                see(self.markers.entry(marker).or_default());
+            }
+        }
+    }
+
+    /// how well the player knows a word, going by the least known of its roots and markers
+    pub fn word(&self, keyword: &Keyword, lang: &Lang) -> Knowledge {
+        keyword.roots().into_iter().map(|r| self.root(r))
+            .chain(keyword.markers(lang).iter().map(|m| self.marker(m)))
+            .min()
+            .unwrap_or(Knowledge::Learned)
+    }
+}
+
+impl Lang {
+    /// a sentence as the player can read it, going by what they've learned: whole words in english,
+    /// words they know part of annotated with it, the rest as they are
+    pub fn reveal(&self, keywords: &[Keyword], vocabulary: &Vocabulary) -> Result<Vec<RevealedWord>, String> {
+        Ok(self.sentence_words(keywords)?
+            .into_iter()
+            .map(|word| match &word.particle {
+                Some(marker) if vocabulary.marker(marker) == Knowledge::Learned => RevealedWord {
+                    text: word.english(self),
+                    reveal: Reveal::Shown,
+                },
+                Some(_) => RevealedWord { text: word.surface, reveal: Reveal::Hidden },
+                None => self.reveal_word(word, vocabulary),
+            })
+            .collect())
+    }
+
+    /// like `Lang::reveal`, as one line of text
+    pub fn render_revealed(&self, text: &str, vocabulary: &Vocabulary) -> Result<String, String> {
+        let keywords = self.parse(text)
+            .map_err(|errors| errors.iter().map(|e| e.to_string()).collect::<Vec<String>>().join("\n"))?;
+        let words = self.reveal(&keywords, vocabulary)?;
+        Ok(words.into_iter().map(|w| w.text).collect::<Vec<String>>().join(" "))
+    }
+
+    fn reveal_word(&self, word: SentenceWord, vocabulary: &Vocabulary) -> RevealedWord {
+        let keyword = &word.keyword;
+        let learned = |knowledge: Knowledge| knowledge == Knowledge::Learned;
+        let roots: Vec<(&str, bool)> = keyword.roots().into_iter().map(|r| (r, learned(vocabulary.root(r)))).collect();
+        let markers: Vec<(String, bool)> = keyword.markers(self).into_iter().map(|m| {
+            let known = learned(vocabulary.marker(&m));
+            (m, known)
+        }).collect();
+
+        if roots.iter().all(|(_, known)| *known) && markers.iter().all(|(_, known)| *known) {
+            return RevealedWord { text: word.english(self), reveal: Reveal::Shown };
+        }
+        if !roots.iter().any(|(_, known)| *known) && !markers.iter().any(|(_, known)| *known) {
+            return RevealedWord { text: word.surface, reveal: Reveal::Hidden };
+        }
+        let pieces: Vec<String> = roots.iter()
+            .map(|(root, known)| if *known {self.english_root(root, root_keyword(keyword))} else {"?".to_string()})
+            .chain(markers.into_iter().map(|(marker, known)| if known {marker} else {"?".to_string()}))
+            .collect();
+        RevealedWord { text: format!("{} ({})", word.surface, pieces.join("-")), reveal: Reveal::Partial }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATS: &str = "artDef{nspac} nom{cat 1} verb{sleep past neg}";
+
+    fn shown(lang: &Lang) -> Vocabulary {
+        let mut vocabulary = Vocabulary::new();
+        vocabulary.show(&lang.parse(CATS).unwrap(), lang);
+        vocabulary
+    }
+
+    #[test]
+    fn showing_a_sentence_sees_its_roots_and_markers() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let mut vocabulary = shown(&lang);
+        assert_eq!(vocabulary.roots.keys().collect::<Vec<_>>(), vec!["cat", "sleep"]);
+        // markers on words, agreement the verb picked up, and particles
+        assert_eq!(vocabulary.markers.keys().collect::<Vec<_>>(), vec!["3PL", "DEF.NSPAC", "NEG", "PL", "PST"]);
+        assert_eq!(vocabulary.root("cat"), Knowledge::Seen);
+        assert_eq!(vocabulary.marker("NEG"), Knowledge::Seen);
+        assert_eq!(vocabulary.root("fire"), Knowledge::Unseen);
+
+        vocabulary.show(&lang.parse("nom{cat 0}").unwrap(), &lang);
+        assert_eq!((vocabulary.roots["cat"].seen, vocabulary.roots["sleep"].seen), (2, 1));
+    }
+
+    #[test]
+    fn knowledge_only_goes_up() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let mut vocabulary = shown(&lang);
+        vocabulary.set_root("cat", Knowledge::Guessed);
+        assert_eq!(vocabulary.root("cat"), Knowledge::Guessed);
+        vocabulary.set_root("cat", Knowledge::Learned);
+        vocabulary.set_root("cat", Knowledge::Guessed);
+        assert_eq!(vocabulary.root("cat"), Knowledge::Learned);
+        // seeing it again doesn't take it back either
+        vocabulary.show(&lang.parse("nom{cat 0}").unwrap(), &lang);
+        assert_eq!(vocabulary.root("cat"), Knowledge::Learned);
+
+        vocabulary.set_marker("PL", Knowledge::Learned);
+        assert_eq!(vocabulary.marker("PL"), Knowledge::Learned);
+    }
+
+    #[test]
+    fn a_word_is_known_as_well_as_its_least_known_part() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let mut vocabulary = shown(&lang);
+        let cats = Keyword::Nominative("cat".to_string(), NounNumber::Plural, Case::Nominative);
+        vocabulary.set_root("cat", Knowledge::Learned);
+        assert_eq!(vocabulary.word(&cats, &lang), Knowledge::Seen);
+        vocabulary.set_marker("PL", Knowledge::Guessed);
+        assert_eq!(vocabulary.word(&cats, &lang), Knowledge::Guessed);
+        vocabulary.set_marker("PL", Knowledge::Learned);
+        assert_eq!(vocabulary.word(&cats, &lang), Knowledge::Learned);
+        assert_eq!(vocabulary.word(&Keyword::Nominative("fire".to_string(), NounNumber::Singular, Case::Nominative), &lang), Knowledge::Unseen);
+    }
+
+    #[test]
+    fn reveals_what_has_been_learned() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let mut vocabulary = shown(&lang);
+        let surface = lang.render(CATS).unwrap().trim().to_string();
+        assert_eq!(lang.render_revealed(CATS, &vocabulary).unwrap(), surface);
+
+        // english for whole words, what's known of the others after them
+        vocabulary.set_root("cat", Knowledge::Learned);
+        vocabulary.set_root("sleep", Knowledge::Learned);
+        vocabulary.set_marker("PL", Knowledge::Learned);
+        let words = lang.reveal(&lang.parse(CATS).unwrap(), &vocabulary).unwrap();
+        let reveals: Vec<Reveal> = words.iter().map(|w| w.reveal).collect();
+        assert_eq!(reveals, vec![Reveal::Hidden, Reveal::Shown, Reveal::Hidden, Reveal::Partial]);
+        assert_eq!(words[1].text, "cats");
+        assert!(words[3].text.ends_with(" (sleep-?-?)"), "{}", words[3].text);
+
+        // a particle's meaning is its own, not the verb's
+        for marker in ["DEF.NSPAC", "PST", "3PL", "NEG"] {
+            vocabulary.set_marker(marker, Knowledge::Learned);
+        }
+        assert_eq!(lang.render_revealed(CATS, &vocabulary).unwrap(), "the cats not slept");
+        assert!(lang.render_revealed("nom{", &vocabulary).is_err());
+    }
+}
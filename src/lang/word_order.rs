@@ -0,0 +1,198 @@
+use std::ops::Range;
+
+use super::*;
+
+impl Lang {
+    /// the order the words of a sentence come out in, as indices into `keywords`
+    ///
+    /// markup is written in one canonical order, english like with subject, verb, object, prepositions
+    /// and articles first, and `word_order` in forms.yaml says how this language rearranges it
+    pub fn word_order(&self, keywords: &[Keyword]) -> Vec<usize> {
+        let phrases = group_phrases(keywords);
+        self.order_clauses(keywords, &phrases)
+            .into_iter()
+            .flat_map(|p| self.order_phrase(keywords, &phrases[p]))
+            .collect()
+    }
+
+    /// the keywords of one phrase in order
+    fn order_phrase(&self, keywords: &[Keyword], phrase: &Phrase) -> Vec<usize> {
+        let range = phrase.keywords.clone();
+        match phrase.kind {
+            PhraseKind::Noun => self.order_noun_phrase(keywords, range),
+            PhraseKind::Prepositional => {
+                let noun_phrase = self.order_noun_phrase(keywords, range.start + 1..range.end);
+                match self.forms.word_order.adpositions {
+                    Some(Placement::After) => noun_phrase.into_iter().chain([range.start]).collect(),
+                    _ => [range.start].into_iter().chain(noun_phrase).collect(),
+                }
+            },
+            PhraseKind::Verb | PhraseKind::Other => range.collect(),
+        }
+    }
+
+    fn order_noun_phrase(&self, keywords: &[Keyword], range: Range<usize>) -> Vec<usize> {
+        let order = &self.forms.word_order;
+        let Some(head) = range.clone().find(|i| matches!(keywords[*i].category(), "noun" | "pronoun")) else {
+            return range.collect();
+        };
+        let is_modifier = |i: &usize| !matches!(keywords[*i].category(), "article" | "numeral");
+        let articles: Vec<usize> = range.clone().filter(|i| keywords[*i].category() == "article").collect();
+        let numerals: Vec<usize> = range.clone().filter(|i| keywords[*i].category() == "numeral").collect();
+        let before: Vec<usize> = (range.start..head).filter(is_modifier).collect();
+        let after: Vec<usize> = (head + 1..range.end).filter(is_modifier).collect();
+
+        let (before, after) = match order.adjectives {
+            Some(Placement::Before) => ([before, after].concat(), vec![]),
+            Some(Placement::After) => (vec![], [before, after].concat()),
+            None => (before, after),
+        };
+        let (numerals_before, numerals_after) = match order.numerals {
+            Some(Placement::After) => (vec![], numerals),
+            _ => (numerals, vec![]),
+        };
+        let (articles_before, articles_after) = match order.articles {
+            Some(Placement::After) => (vec![], articles),
+            _ => (articles, vec![]),
+        };
+        [articles_before, numerals_before, before, vec![head], after, numerals_after, articles_after].concat()
+    }
+
+    /// the phrases in order, with the subject, verb and object of each clause swapped around
+    ///
+    /// a verb phrase's subject is the noun phrase right before it and its object the one right after,
+    /// unless that one is in some other case than the accusative or is right before another verb and so is its subject instead,
+    /// all three keep the places they were written in between them so anything else stays put
+    fn order_clauses(&self, keywords: &[Keyword], phrases: &[Phrase]) -> Vec<usize> {
+        let mut sequence: Vec<usize> = (0..phrases.len()).collect();
+        let Some(clause) = &self.forms.word_order.clause else {return sequence};
+
+        let is_noun = |i: usize| phrases.get(i).is_some_and(|p| p.kind == PhraseKind::Noun && p.head.is_some());
+        let is_verb = |i: usize| phrases.get(i).is_some_and(|p| p.kind == PhraseKind::Verb);
+        // nouns in any other case are obliques, which stay where they were written
+        let can_be_object = |i: usize| phrases[i].head.is_some_and(|h| match &keywords[h] {
+            Keyword::Nominative(_, _, case) => matches!(case, Case::Nominative | Case::Accusative),
+            _ => true,
+        });
+        let mut used = vec![false; phrases.len()];
+        for verb in (0..phrases.len()).filter(|i| is_verb(*i)) {
+            let subject = verb.checked_sub(1).filter(|s| is_noun(*s) && !used[*s]);
+            let object = Some(verb + 1).filter(|o| is_noun(*o) && can_be_object(*o) && !is_verb(o + 1));
+            let roles = [('s', subject), ('v', Some(verb)), ('o', object)];
+
+            let slots: Vec<usize> = roles.iter().filter_map(|(_, p)| *p).collect();
+            let ordered = clause.chars().filter_map(|c| roles.iter().find(|(r, _)| *r == c).and_then(|(_, p)| *p));
+            for (slot, phrase) in slots.iter().zip(ordered) {
+                sequence[*slot] = phrase;
+                used[phrase] = true;
+            }
+        }
+        sequence
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a language with no word order of its own, markup comes out as written
+    fn as_written() -> Lang {
+        let mut lang = Lang::load("assets/lang").unwrap();
+        lang.forms.word_order = WordOrder::default();
+        lang
+    }
+
+    /// `markup` with its keywords put in the language's order
+    fn order(lang: &Lang, markup: &str) -> String {
+        // keywords are separated by the spaces outside braces
+        let mut written = vec![];
+        let (mut start, mut depth) = (0, 0);
+        for (i, c) in markup.char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                ' ' if depth == 0 => {
+                    written.push(&markup[start..i]);
+                    start = i + 1;
+                },
+                _ => (),
+            }
+        }
+        written.push(&markup[start..]);
+        let keywords = lang.parse(markup).unwrap();
+        assert_eq!(keywords.len(), written.len());
+        lang.word_order(&keywords).into_iter().map(|i| written[i]).collect::<Vec<&str>>().join(" ")
+    }
+
+    #[test]
+    fn clauses_follow_the_declared_order() {
+        let mut lang = as_written();
+        let sentence = "pron{3 sg} verb{hit past} artDef{dist} nom{cat 0 acc}";
+        assert_eq!(order(&lang, sentence), sentence);
+        let orders = [
+            ("svo", "pron{3 sg} verb{hit past} artDef{dist} nom{cat 0 acc}"),
+            ("sov", "pron{3 sg} artDef{dist} nom{cat 0 acc} verb{hit past}"),
+            ("vso", "verb{hit past} pron{3 sg} artDef{dist} nom{cat 0 acc}"),
+            ("osv", "artDef{dist} nom{cat 0 acc} pron{3 sg} verb{hit past}"),
+        ];
+        for (clause, expected) in orders {
+            lang.forms.word_order.clause = Some(clause.to_string());
+            assert_eq!(order(&lang, sentence), expected, "{}", clause);
+        }
+        // a clause missing a part just leaves it out
+        lang.forms.word_order.clause = Some("vso".to_string());
+        assert_eq!(order(&lang, "pron{3 sg} verb{sleep pres}"), "verb{sleep pres} pron{3 sg}");
+    }
+
+    #[test]
+    fn obliques_stay_where_they_were_written() {
+        let mut lang = as_written();
+        lang.forms.word_order.clause = Some("sov".to_string());
+        let sentence = "nom{cat 0} verb{sleep pres} nom{fire 0 loc}";
+        assert_eq!(order(&lang, sentence), sentence);
+        assert_eq!(
+            order(&lang, "nom{cat 0} verb{sleep pres} prep{face} nom{fire 0}"),
+            "nom{cat 0} verb{sleep pres} prep{face} nom{fire 0}",
+        );
+    }
+
+    #[test]
+    fn a_noun_before_a_verb_is_its_subject_not_the_last_ones_object() {
+        let mut lang = as_written();
+        lang.forms.word_order.clause = Some("sov".to_string());
+        assert_eq!(
+            order(&lang, "nom{cat 0} verb{hit past} nom{water 0} verb{run pres} nom{fire 0 acc}"),
+            "nom{cat 0} verb{hit past} nom{water 0} nom{fire 0 acc} verb{run pres}",
+        );
+    }
+
+    #[test]
+    fn noun_phrases_place_adpositions_adjectives_articles_and_numerals() {
+        let mut lang = as_written();
+        let phrase = "prep{face} artDef{dist} num{3} adj{warm} nom{water 1} adj{run}";
+        assert_eq!(order(&lang, phrase), phrase);
+
+        lang.forms.word_order.adpositions = Some(Placement::After);
+        assert_eq!(order(&lang, phrase), "artDef{dist} num{3} adj{warm} nom{water 1} adj{run} prep{face}");
+        lang.forms.word_order.adpositions = None;
+
+        lang.forms.word_order.adjectives = Some(Placement::After);
+        assert_eq!(order(&lang, phrase), "prep{face} artDef{dist} num{3} nom{water 1} adj{warm} adj{run}");
+        lang.forms.word_order.adjectives = Some(Placement::Before);
+        assert_eq!(order(&lang, phrase), "prep{face} artDef{dist} num{3} adj{warm} adj{run} nom{water 1}");
+        lang.forms.word_order.adjectives = None;
+
+        lang.forms.word_order.articles = Some(Placement::After);
+        lang.forms.word_order.numerals = Some(Placement::After);
+        assert_eq!(order(&lang, phrase), "prep{face} adj{warm} nom{water 1} adj{run} num{3} artDef{dist}");
+    }
+
+    #[test]
+    fn the_shipped_language_is_sov_with_postpositions() {
+        let lang = Lang::load("assets/lang").unwrap();
+        assert_eq!(
+            order(&lang, "pron{3 sg} verb{hit past} artDef{dist} adj{warm} nom{cat 0 acc} prep{face} nom{fire 0}"),
+            "pron{3 sg} artDef{dist} nom{cat 0 acc} adj{warm} verb{hit past} nom{fire 0} prep{face}",
+        );
+    }
+}
@@ -1,14 +1,28 @@
 use raylib::prelude::*;
 
-use crate::{lang::{to_object, Lang}, ui::{Element, ElementTraits, Label}};
+use crate::{lang::Lang, save::Save, ui::{Element, ElementTraits, Label}};
 
 pub mod draw;
 pub mod settings;
 pub mod ui;
 pub mod lang;
 pub mod debug;
+pub mod notebook;
+pub mod save;
 
 fn main() {
+    // `lingot reference docs/lang.md` (or `.html`) writes out the language reference instead of starting the game
+    let args: Vec<String> = std::env::args().collect();
+    if let [_, command, path] = args.as_slice() && command == "reference" {
+        let lang = Lang::load("assets/lang").unwrap_or_else(|e| panic!("{}", e));
+        let reference = if path.ends_with(".html") {lang.reference_html()} else {lang.reference_markdown()};
+        if let Err(e) = std::fs::write(path, reference) {
+            eprintln!("Failed to write {}: {}", path, e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let (mut rl, thread) = raylib::init()
         .size(640, 480)
         .resizable()
@@ -44,20 +58,99 @@ fn main() {
         Err(e) => panic!("{}", e)
     };
     // println!("{}", lang.render("artIndef{prox} nom{young 1} verb{run past} prep{close} artDef{dist} nom{water 0} adj{run}"));
+    let render = |markup: &str| match lang.render(markup) {
+        Ok(s) => s,
+        Err(errors) => errors.iter().map(|e| e.to_string()).collect::<Vec<String>>().join("\n"),
+    };
+
+    let gloss = |markup: &str| {
+        let mut out = match lang.gloss(markup) {
+            Ok(g) => g.to_string(),
+            Err(e) => e,
+        };
+        // which sound changes got the surface line there
+        if let Ok((_, firings)) = lang.render_with_trace(markup) {
+            for firing in firings {
+                out.push_str(&format!("\n  word {}: {} -> {} by `{}`", firing.word + 1, firing.before, firing.after, firing.rule));
+            }
+        }
+        out
+    };
+
+    const RIVER: &str = "artIndef{prox} nom{young 1} aspPerf verb{run past} prep{face} artDef{dist} nom{water 0} adj{run}";
+    const CAT: &str = "artDef{nspac} nom{cat 0} aspProg verb{sleep pres} artDef{nspac} nom{fire 0 loc}";
+
+    let english = |markup: &str| match lang.translate_to_english(markup) {
+        Ok(s) => s,
+        Err(errors) => errors.iter().map(|e| e.to_string()).collect::<Vec<String>>().join("\n"),
+    };
 
     let rendered_text = format!(
         "{}\n{}\n{}\n\n{}\n{}\n{}",
-        "some children have ran toward that river",
-        "artIndef{prox} nom{young 1} aspPerf verb{run past} prep{face} artDef{dist} nom{water 0} adj{run}",
-        lang.render("artIndef{prox} nom{young 1} aspPerf verb{run past} prep{face} artDef{dist} nom{water 0} adj{run}"),
-        "the cat sleeps near the fire",
-        "artDef{nspac} nom{cat 0} aspProg verb{sleep pres} prep{close} artDef{nspac} nom{fire 0}",
-        lang.render("artDef{nspac} nom{cat 0} aspProg verb{sleep pres} prep{close} artDef{nspac} nom{fire 0}")
+        english(RIVER),
+        RIVER,
+        render(RIVER),
+        english(CAT),
+        CAT,
+        render(CAT)
     );
     println!("{}", rendered_text);
 
-    println!("\n\n");
-    dbg!(to_object("artIndef{prox} nom{young 1} aspPerf verb{run past} prep{face} artDef{dist} nom{water 0} adj{run}"));
+    for markup in [RIVER, CAT] {
+        match lang.check_markup(markup) {
+            Ok(warnings) => for warning in warnings {eprintln!("grammar: {}", warning)},
+            Err(errors) => for error in errors {eprintln!("markup: {}", error)},
+        }
+    }
+
+    // a save that won't load is moved aside and the game starts over, rather than not starting at all
+    let mut save = Save::load(save::SAVE_PATH).unwrap_or_else(|e| {
+        match Save::set_aside(save::SAVE_PATH) {
+            Ok(aside) => eprintln!("{}\nstarting a new game, the old save is in {}", e, aside),
+            Err(aside_error) => eprintln!("{}\n{}", e, aside_error),
+        }
+        Save::default()
+    });
+
+    // both sentences are on screen, so the player has seen every word in them
+    let mut shown_new = false;
+    for markup in [RIVER, CAT] {
+        match save.show(&lang, markup) {
+            Ok(new) => shown_new |= new,
+            Err(e) => eprintln!("notebook: {}", e),
+        }
+    }
+    if shown_new && let Err(e) = save.write(save::SAVE_PATH) {
+        eprintln!("{}", e);
+    }
+
+    // debug screen, toggled with f1
+    let gloss_text = format!(
+        "{}\n\n{}\n\n{}",
+        "interlinear gloss (f1 to toggle)",
+        gloss(RIVER),
+        gloss(CAT)
+    );
+
+    let mut gloss_panel = ui::Panel::new(0, 0, rl.get_screen_width(), rl.get_screen_height(), Color::BLACK);
+    gloss_panel.add_child(
+        Element::Label(
+            Label::new(
+                gloss_text.as_str(),
+                10, 10, Color::WHITE,
+            )
+        ),
+        "gloss"
+    );
+    let mut show_gloss = false;
+
+    // notebook screen, toggled with f2, up and down pick a word and enter writes down what's typed as a guess for it
+    let mut show_notebook = false;
+    let mut selected = 0;
+    let line_height = context.font.char_dim.y as i32;
+    let mut guess_edit = ui::TextEdit::new(10, 10 + line_height, rl.get_screen_width() - 20, line_height + 4, Color::DARKGRAY, Color::WHITE);
+    // how the last guess went, at the bottom of the notebook
+    let mut status = String::new();
 
     while !rl.window_should_close() {
         // logic ---------------------------------------------------------------------------------
@@ -66,6 +159,48 @@ fn main() {
             context.font.resize(&rl);
             screen_panel.dim.z = rl.get_screen_width() as f32;
             screen_panel.dim.w = rl.get_screen_height() as f32;
+            gloss_panel.dim.z = rl.get_screen_width() as f32;
+            gloss_panel.dim.w = rl.get_screen_height() as f32;
+            guess_edit.dim().z = (rl.get_screen_width() - 20) as f32;
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_F1) {
+            show_gloss = !show_gloss;
+        }
+
+        let words: Vec<String> = save.notebook.by_frequency().into_iter().map(|(word, _)| word.clone()).collect();
+        let mut picked = rl.is_key_pressed(KeyboardKey::KEY_F2);
+        if picked {
+            show_notebook = !show_notebook;
+            guess_edit.focused = show_notebook;
+        }
+        if show_notebook && !words.is_empty() {
+            if rl.is_key_pressed(KeyboardKey::KEY_DOWN) {
+                selected = (selected + 1) % words.len();
+                picked = true;
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_UP) {
+                selected = (selected + words.len() - 1) % words.len();
+                picked = true;
+            }
+            selected = selected.min(words.len() - 1);
+            if picked {
+                guess_edit.text = save.notebook.words[&words[selected]].guess.clone().unwrap_or_default();
+            }
+            if guess_edit.update(&mut rl) {
+                status = match save.notebook.guess(&words[selected], &guess_edit.text, &mut save.vocabulary) {
+                    Ok(_) if guess_edit.text.trim().is_empty() => format!("rubbed out the guess for {}", words[selected]),
+                    Ok(right) => format!("{} for {} is {}", guess_edit.text.trim(), words[selected], if right {"right"} else {"wrong"}),
+                    Err(e) => {
+                        eprintln!("notebook: {}", e);
+                        e
+                    }
+                };
+                if let Err(e) = save.write(save::SAVE_PATH) {
+                    eprintln!("{}", e);
+                    status = format!("couldn't save: {}", e);
+                }
+            }
         }
 
         ui::with_element::<ui::Label, _>(&screen_panel.children, "label1", ui::matcher::label, |label| {
@@ -77,6 +212,36 @@ fn main() {
 
         d.clear_background(Color::RED); // red so i can see when things fuck up
         
-        screen_panel.render(0, 0, &mut context, &mut d);
+        if show_notebook {
+            // rebuilt every frame, the lines change with every guess
+            let mut lines = vec!["notebook (f2 to close)".to_string(), String::new()];
+            for (i, word) in words.iter().enumerate() {
+                let entry = &save.notebook.words[word];
+                let marker = if i == selected {">"} else {" "};
+                let guess = entry.guess.as_deref().unwrap_or("?");
+                lines.push(format!("{} {:<16} seen {:<3} {}", marker, word, entry.seen, guess));
+            }
+            if let Some(word) = words.get(selected) {
+                lines.push(String::new());
+                lines.extend(save.notebook.words[word].sentences.iter().map(|s| format!("  {}", s)));
+            }
+            if !status.is_empty() {
+                lines.push(String::new());
+                lines.push(status.clone());
+            }
+
+            let mut notebook_panel = ui::Panel::new(0, 0, d.get_screen_width(), d.get_screen_height(), Color::BLACK);
+            for (i, line) in lines.iter().enumerate() {
+                // the guess box goes on the second line
+                let y = 10 + (i as i32 + if i > 0 {1} else {0}) * line_height;
+                notebook_panel.add_child(Element::Label(Label::new(line, 10, y, Color::WHITE)), &format!("line{}", i));
+            }
+            notebook_panel.render(0, 0, &mut context, &mut d);
+            guess_edit.render(0, 0, &mut context, &mut d);
+        } else if show_gloss {
+            gloss_panel.render(0, 0, &mut context, &mut d);
+        } else {
+            screen_panel.render(0, 0, &mut context, &mut d);
+        }
     }
 }
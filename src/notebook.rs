@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::lang::{dictionary::normalize, Keyword, Knowledge, Lang, Vocabulary};
+
+/// what the player has written down about one word, and what the game knows about it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotebookEntry {
+    /// what the player thinks it means, in their own words
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guess: Option<String>,
+    /// how many times it's been shown
+    pub seen: usize,
+    /// the sentences it was in, as the player saw them, each only once
+    #[serde(default)]
+    pub sentences: Vec<String>,
+    /// the roots it's built on, a right guess teaches them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roots: Vec<String>,
+    /// the markers a word without roots stands for, like `DEF.DIST` for an article, a right guess teaches them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub markers: Vec<String>,
+    /// the english a guess counts as right with
+    #[serde(default)]
+    pub meanings: Vec<String>,
+}
+
+/// the player's notes on the words they've seen, keyed by the words as they're written
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Notebook {
+    #[serde(default)]
+    pub words: BTreeMap<String, NotebookEntry>,
+}
+
+impl NotebookEntry {
+    /// whether the guess means the same as the word, ignoring case and any "to" or article, `None` without a guess
+    pub fn is_right(&self) -> Option<bool> {
+        let guess = normalize(self.guess.as_ref()?);
+        Some(self.meanings.iter().any(|m| normalize(m) == guess))
+    }
+}
+
+impl Notebook {
+    pub fn new() -> Notebook {
+        Notebook::default()
+    }
+
+    /// writes down every word of a sentence being shown to the player, along with the sentence
+    pub fn record(&mut self, lang: &Lang, keywords: &[Keyword]) -> Result<(), String> {
+        let words = lang.sentence_words(keywords)?;
+        let sentence = words.iter().map(|w| w.surface.as_str()).collect::<Vec<&str>>().join(" ");
+        for word in &words {
+            let entry = self.words.entry(word.surface.clone()).or_default();
+            entry.seen += 1;
+            if !entry.sentences.contains(&sentence) {
+                entry.sentences.push(sentence.clone());
+            }
+
+            // a word made of one root is right with any of the root's meanings, not just the one its inflection gives
+            let roots = if word.particle.is_none() {word.keyword.roots()} else {vec![]};
+            let markers = match &word.particle {
+                Some(marker) => vec![marker.clone()],
+                None if roots.is_empty() => word.keyword.markers(lang),
+                None => vec![],
+            };
+            let mut meanings = vec![word.english(lang)];
+            if let [root] = roots[..] {
+                meanings.extend(lang.root(root).map_or(vec![], |r| r.english(root)).into_iter().map(String::from));
+            }
+
+            for (list, new) in [(&mut entry.roots, roots.into_iter().map(String::from).collect()), (&mut entry.markers, markers), (&mut entry.meanings, meanings)] {
+                for item in new {
+                    if !list.contains(&item) {list.push(item)}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// writes down the player's guess for a word they've seen, an empty one rubbing it out,
+    /// and has the vocabulary count what the word is made of as guessed, or learned if the guess is right
+    ///
+    /// returns whether it's right, errors if the word isn't in the notebook
+    pub fn guess(&mut self, word: &str, guess: &str, vocabulary: &mut Vocabulary) -> Result<bool, String> {
+        let entry = self.words.get_mut(word).ok_or(format!("`{}` isn't in the notebook", word))?;
+        let guess = guess.trim();
+        if guess.is_empty() {
+            entry.guess = None;
+            return Ok(false);
+        }
+        entry.guess = Some(guess.to_string());
+        let right = entry.is_right() == Some(true);
+        let knowledge = if right {Knowledge::Learned} else {Knowledge::Guessed};
+        for root in &entry.roots {
+            vocabulary.set_root(root, knowledge);
+        }
+        for marker in &entry.markers {
+            vocabulary.set_marker(marker, knowledge);
+        }
+        Ok(right)
+    }
+
+    /// every word, the ones seen most first
+    pub fn by_frequency(&self) -> Vec<(&String, &NotebookEntry)> {
+        let mut words: Vec<(&String, &NotebookEntry)> = self.words.iter().collect();
+        words.sort_by(|a, b| b.1.seen.cmp(&a.1.seen).then(a.0.cmp(b.0)));
+        words
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAT: &str = "artDef{nspac} nom{cat 0} aspProg verb{sleep pres} artDef{nspac} nom{fire 0 loc}";
+
+    fn recorded(lang: &Lang) -> Notebook {
+        let mut notebook = Notebook::new();
+        notebook.record(lang, &lang.parse(CAT).unwrap()).unwrap();
+        notebook
+    }
+
+    #[test]
+    fn records_each_word_with_its_sentence() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let mut notebook = recorded(&lang);
+        let sentence = "nan kita apa sapara nan fiyane";
+        assert_eq!(notebook.words["nan"].seen, 2);
+        assert_eq!(notebook.words["kita"].seen, 1);
+        assert_eq!(notebook.words["nan"].markers, vec!["DEF.NSPAC"]);
+        assert_eq!(notebook.words["fiyane"].roots, vec!["fire"]);
+        // the inflected meaning and the root's own both count
+        assert_eq!(notebook.words["fiyane"].meanings, vec!["at fire", "fire"]);
+
+        // a sentence is written down once however often it's shown
+        notebook.record(&lang, &lang.parse(CAT).unwrap()).unwrap();
+        assert_eq!(notebook.words["kita"].seen, 2);
+        assert_eq!(notebook.words["kita"].sentences, vec![sentence]);
+        assert_eq!(notebook.by_frequency()[0].0, "nan");
+    }
+
+    #[test]
+    fn right_guesses_teach_and_wrong_ones_only_count_as_guessed() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let mut notebook = recorded(&lang);
+        let mut vocabulary = Vocabulary::new();
+
+        assert_eq!(notebook.guess("sapara", "  To Sleep ", &mut vocabulary), Ok(true));
+        assert_eq!(notebook.words["sapara"].guess.as_deref(), Some("To Sleep"));
+        assert_eq!(vocabulary.root("sleep"), Knowledge::Learned);
+
+        assert_eq!(notebook.guess("nan", "a", &mut vocabulary), Ok(false));
+        assert_eq!(notebook.words["nan"].is_right(), Some(false));
+        assert_eq!(vocabulary.marker("DEF.NSPAC"), Knowledge::Guessed);
+
+        // a wrong guess after a right one doesn't unlearn anything
+        assert_eq!(notebook.guess("sapara", "cat", &mut vocabulary), Ok(false));
+        assert_eq!(vocabulary.root("sleep"), Knowledge::Learned);
+    }
+
+    #[test]
+    fn empty_guesses_rub_out_and_unseen_words_are_errors() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let mut notebook = recorded(&lang);
+        let mut vocabulary = Vocabulary::new();
+
+        notebook.guess("kita", "cat", &mut vocabulary).unwrap();
+        assert_eq!(notebook.guess("kita", "   ", &mut vocabulary), Ok(false));
+        assert_eq!(notebook.words["kita"].guess, None);
+        assert_eq!(notebook.words["kita"].is_right(), None);
+
+        assert!(notebook.guess("zzz", "cat", &mut vocabulary).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::debug::ResultExt;
+use crate::lang::{Lang, Vocabulary};
+use crate::notebook::Notebook;
+
+/// where the game is saved, relative to where it's run from like `assets`
+pub const SAVE_PATH: &str = "save.yaml";
+
+/// everything about a game that's kept between runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Save {
+    #[serde(default)]
+    pub vocabulary: Vocabulary,
+    #[serde(default)]
+    pub notebook: Notebook,
+    /// the markup of every sentence the player has been shown
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub shown: BTreeSet<String>,
+}
+
+impl Save {
+    /// reads a save, a new game if there's nothing at `path` yet
+    pub fn load(path: &str) -> Result<Save, Box<dyn Error>> {
+        if !Path::new(path).exists() {
+            return Ok(Save::default());
+        }
+        let text = fs::read_to_string(path).ctx(format!("Failed to read save from {}", path))?;
+        Ok(serde_yaml::from_str(&text).ctx(format!("Failed to parse save {}", path))?)
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let text = serde_yaml::to_string(self).ctx("Failed to serialize save")?;
+        fs::write(path, text).ctx(format!("Failed to write save to {}", path))?;
+        Ok(())
+    }
+
+    /// moves a save that won't load out of the way so a new game doesn't write over it, returns where it went
+    pub fn set_aside(path: &str) -> Result<String, Box<dyn Error>> {
+        let aside = format!("{}.broken", path);
+        fs::rename(path, &aside).ctx(format!("Failed to move save {} to {}", path, aside))?;
+        Ok(aside)
+    }
+
+    /// counts a sentence's words as seen and writes them in the notebook, the first time it's shown only,
+    /// so a sentence on screen again after a restart isn't counted again
+    ///
+    /// returns whether it hadn't been shown before
+    pub fn show(&mut self, lang: &Lang, markup: &str) -> Result<bool, String> {
+        if self.shown.contains(markup) {
+            return Ok(false);
+        }
+        let keywords = lang.parse(markup).map_err(|errors| errors.iter().map(|e| e.to_string()).collect::<Vec<String>>().join("\n"))?;
+        self.notebook.record(lang, &keywords)?;
+        self.vocabulary.show(&keywords, lang);
+        self.shown.insert(markup.to_string());
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lang::Knowledge;
+
+    const CAT: &str = "artDef{nspac} nom{cat 0} aspProg verb{sleep pres} artDef{nspac} nom{fire 0 loc}";
+
+    /// a file in the temp dir for one test, named so tests running at once never share one
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir().join(format!("lingot-{}-{}.yaml", name, std::process::id())).to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn sentences_are_only_counted_the_first_time() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let mut save = Save::default();
+        assert_eq!(save.show(&lang, CAT), Ok(true));
+        assert_eq!(save.show(&lang, CAT), Ok(false));
+        assert_eq!(save.notebook.words["kita"].seen, 1);
+        assert_eq!(save.vocabulary.roots["cat"].seen, 1);
+        assert!(save.show(&lang, "nom{").is_err());
+    }
+
+    #[test]
+    fn round_trips_through_a_file() {
+        let lang = Lang::load("assets/lang").unwrap();
+        let mut save = Save::default();
+        save.show(&lang, CAT).unwrap();
+        save.notebook.guess("kita", "cat", &mut save.vocabulary).unwrap();
+        assert_eq!(save.vocabulary.root("cat"), Knowledge::Learned);
+
+        let path = temp_path("round-trip");
+        save.write(&path).unwrap();
+        let loaded = Save::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, save);
+    }
+
+    #[test]
+    fn missing_saves_are_new_games_and_broken_ones_can_be_set_aside() {
+        let path = temp_path("broken");
+        assert_eq!(Save::load(&path).unwrap(), Save::default());
+
+        fs::write(&path, "notebook: [not, a, notebook]").unwrap();
+        assert!(Save::load(&path).is_err());
+        let aside = Save::set_aside(&path).unwrap();
+        assert!(!Path::new(&path).exists());
+        fs::remove_file(aside).unwrap();
+    }
+}
@@ -11,16 +11,25 @@ use super::*;
 
 pub struct TextEdit<'a> {
     panel: Panel<'a>,
-    label: Label<'a>,
 
-    pub text: &'a str,
+    pub text: String,
+    pub text_color: Color,
+    /// whether typing goes into it
+    pub focused: bool,
     pub children: HashMap<String, Rc<RefCell<Element<'a>>>>
 }
 
 impl<'a> ElementTraits<'a> for TextEdit<'a> {
     fn render(&self, parent_x: i32, parent_y: i32, context: &mut draw::Context, draw_handle: &mut RaylibDrawHandle) {
         self.panel.render(parent_x, parent_y, context, draw_handle);
-        self.label.render(parent_x, parent_y, context, draw_handle);
+        let cursor = if self.focused {"_"} else {""};
+        context.draw_text(
+            draw_handle,
+            &format!("{}{}", self.text, cursor),
+            self.panel.dim.x as i32 + parent_x + 4,
+            self.panel.dim.y as i32 + parent_y + 2,
+            self.text_color,
+        );
     }
 
     fn get_child(&self, id: &str) -> Option<Rc<RefCell<Element<'a>>>> {
@@ -33,11 +42,31 @@ impl<'a> ElementTraits<'a> for TextEdit<'a> {
 }
 
 impl<'a> TextEdit<'a> {
-    pub fn new() -> TextEdit<'a> {
-        return todo!()
+    pub fn new(x: i32, y: i32, w: i32, h: i32, color: Color, text_color: Color) -> TextEdit<'a> {
+        TextEdit {
+            panel: Panel::new(x, y, w, h, color),
+            text: String::new(),
+            text_color,
+            focused: false,
+            children: HashMap::new(),
+        }
     }
 
-    pub fn update(&mut self) {
-        self.label.text = self.text;
+    /// takes whatever was typed since the last frame if it's focused, returns true when enter was pressed
+    pub fn update(&mut self, rl: &mut RaylibHandle) -> bool {
+        if !self.focused {
+            return false;
+        }
+        while let Some(c) = rl.get_char_pressed() {
+            self.text.push(c);
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) || rl.is_key_pressed_repeat(KeyboardKey::KEY_BACKSPACE) {
+            self.text.pop();
+        }
+        rl.is_key_pressed(KeyboardKey::KEY_ENTER)
     }
-}
\ No newline at end of file
+
+    pub fn dim(&mut self) -> &mut Vector4 {
+        &mut self.panel.dim
+    }
+}